@@ -3,6 +3,7 @@
 extern crate test;
 
 use gap_solver::{solve, GapSpec};
+use std::collections::HashMap;
 use test::Bencher;
 
 #[bench]
@@ -17,6 +18,12 @@ fn bench_medium(b: &mut Bencher) {
     b.iter(|| solve(&spec));
 }
 
+#[bench]
+fn bench_large(b: &mut Bencher) {
+    let spec = setup_large();
+    b.iter(|| solve(&spec));
+}
+
 fn setup_small<'a>() -> GapSpec<&'a str, &'a str, u32, f64> {
     let agents = ["a", "b", "c"];
     let tasks = ["1", "2"];
@@ -77,3 +84,26 @@ fn setup_medium<'a>() -> GapSpec<&'a str, &'a str, u32, u32> {
     spec.set_profits(profits);
     spec
 }
+
+fn setup_large() -> GapSpec<u32, u32, u32, u32> {
+    let agents: Vec<u32> = (0..12).collect();
+    let tasks: Vec<u32> = (0..24).collect();
+    let mut spec = GapSpec::new(agents.clone(), tasks.clone());
+
+    let agent_budgets: Vec<(u32, u32)> = agents.iter().map(|a| (*a, 3)).collect();
+    spec.set_agent_budgets(agent_budgets);
+
+    // Deterministic pseudo-random profits and agent costs
+    let mut profits = Vec::new();
+    let mut agent_cost = HashMap::new();
+    for a in &agents {
+        for t in &tasks {
+            let x = (a * 31 + t * 17 + a * t * 7) % 23;
+            profits.push(((*a, *t), x % 20 + 1));
+            agent_cost.insert((*a, *t), x % 2 + 1);
+        }
+    }
+    spec.set_profits(profits);
+    spec.set_agent_cost(agent_cost);
+    spec
+}
@@ -1,4 +1,5 @@
-use crate::spec::GapSpec;
+use crate::error::{GapError, GapResult};
+use crate::spec::{first_exceeded, GapSpec};
 use num::Num;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
@@ -7,13 +8,17 @@ use std::hash::{Hash, Hasher};
 use std::ops::{AddAssign, SubAssign};
 
 /// An assignment of agents to tasks.
-/// Tracks agent and task budgets and the total profit.
+/// Tracks agent and task budgets and resources, and the total profit
+/// along with the profit of every agent.
 #[derive(Clone)]
 pub struct Assignment<'a, A, T, C, P> {
     assigned: BTreeMap<A, BTreeSet<T>>,
     agent_budgets: HashMap<A, C>,
     task_budgets: HashMap<T, C>,
+    agent_resources: HashMap<A, Vec<C>>,
+    task_resources: HashMap<T, Vec<C>>,
     profit: P,
+    agent_profits: HashMap<A, P>,
     spec: &'a GapSpec<A, T, C, P>,
 }
 
@@ -25,19 +30,31 @@ where
     P: Num + AddAssign + PartialOrd + Copy,
 {
     /// Create an Assignment from the given problem specification.
+    /// Panics if the spec is inconsistent; see `GapSpec::validate`.
     pub fn from_spec(spec: &'a GapSpec<A, T, C, P>) -> Self {
         // Initialize empty assignment
         let mut assignment = Self {
             assigned: BTreeMap::new(),
             agent_budgets: spec.agent_budgets().clone(),
             task_budgets: spec.task_budgets().clone(),
+            agent_resources: spec.agent_resources().clone(),
+            task_resources: spec.task_resources().clone(),
             profit: P::zero(),
+            agent_profits: spec.agents().iter().map(|a| (*a, P::zero())).collect(),
             spec,
         };
-        // Handle agents that are already assigned
+        // Handle agents that are already assigned, and pairs that are required
         for (agent, tasks) in spec.assigned() {
             for task in tasks {
-                assignment.assign(agent, task).unwrap();
+                assignment.assign_fitting(agent, task);
+            }
+        }
+        for (agent, task) in spec.required() {
+            if !assignment
+                .agent_tasks(agent)
+                .is_some_and(|ts| ts.contains(task))
+            {
+                assignment.assign_fitting(agent, task);
             }
         }
         assignment
@@ -54,45 +71,142 @@ where
             assigned: BTreeMap::new(),
             agent_budgets: spec.agent_budgets().clone(),
             task_budgets: spec.task_budgets().clone(),
+            agent_resources: spec.agent_resources().clone(),
+            task_resources: spec.task_resources().clone(),
             profit: P::zero(),
+            agent_profits: spec.agents().iter().map(|a| (*a, P::zero())).collect(),
             spec,
         };
         // Handle agents that are already assigned
         for (agent, tasks) in assigned {
             for task in tasks {
-                assignment.assign(&agent, &task).unwrap();
+                assignment.assign_fitting(&agent, &task);
             }
         }
         assignment
     }
 
-    /// Assign an agent to a task
-    pub fn assign(&mut self, agent: &A, task: &T) -> Result<(), &str> {
+    /// Assign an agent to a task that is known to fit the remaining budgets.
+    pub(crate) fn assign_fitting(&mut self, agent: &A, task: &T) {
+        if self.assign(agent, task).is_err() {
+            panic!("Assigned pair does not fit the budgets.");
+        }
+    }
+
+    /// Assign an agent to a task.
+    /// Returns an error if the agent or task is unknown, if the agent may not take
+    /// the task or already holds it, or if the task does not fit the budgets, resources,
+    /// conflicting tasks or incompatible agents.
+    pub fn assign(&mut self, agent: &A, task: &T) -> GapResult<(), A, T, C> {
+        // Check agent and task
+        let agent_budget = self
+            .agent_budgets
+            .get_mut(agent)
+            .ok_or(GapError::UnknownAgent(*agent))?;
+        let task_budget = self
+            .task_budgets
+            .get_mut(task)
+            .ok_or(GapError::UnknownTask(*task))?;
+        // Check eligibility
+        if !self.spec.is_eligible(agent, task) {
+            return Err(GapError::IneligiblePair {
+                agent: *agent,
+                task: *task,
+            });
+        }
+        // Check incompatible agents
+        let assigned = &self.assigned;
+        if let Some(other) = self
+            .spec
+            .incompatible_agents(agent)
+            .find(|other| assigned.get(other).is_some_and(|ts| ts.contains(task)))
+        {
+            return Err(GapError::IncompatibleAgents {
+                agent: *agent,
+                task: *task,
+                other: *other,
+            });
+        }
         // Check assigned tasks
-        let tasks = self.assigned.entry(*agent).or_insert_with(BTreeSet::new);
+        let tasks = self.assigned.entry(*agent).or_default();
         if tasks.contains(task) {
-            return Err("Cannot assign agent to the same task twice.");
+            return Err(GapError::DuplicateAssignment {
+                agent: *agent,
+                task: *task,
+            });
+        }
+        // Check conflicting tasks
+        if let Some(conflict) = self
+            .spec
+            .conflicting_tasks(task)
+            .find(|t| tasks.contains(t))
+        {
+            return Err(GapError::ConflictingTasks {
+                agent: *agent,
+                task: *task,
+                conflict: *conflict,
+            });
         }
         // Check agent budget
         let agent_spent = self.spec.agent_cost(agent, task);
-        let agent_budget = self.agent_budgets.get_mut(agent).unwrap();
         if agent_spent > *agent_budget {
-            return Err("Agent does not have enough budget for task.");
+            return Err(GapError::AgentBudgetExceeded {
+                agent: *agent,
+                needed: agent_spent,
+                remaining: *agent_budget,
+            });
         }
         // Check task budget
         let task_spent = self.spec.task_cost(agent, task);
-        let task_budget = self.task_budgets.get_mut(task).unwrap();
         if task_spent > *task_budget {
-            return Err("Task does not have enough budget for agent.");
+            return Err(GapError::TaskBudgetExceeded {
+                task: *task,
+                needed: task_spent,
+                remaining: *task_budget,
+            });
+        }
+        // Check agent and task resources
+        let agent_costs = self.spec.agent_resource_costs(agent, task);
+        if let Some(remaining) = self.agent_resources.get(agent) {
+            if let Some(resource) = first_exceeded(agent_costs, remaining) {
+                return Err(GapError::AgentResourceExceeded {
+                    agent: *agent,
+                    resource,
+                    needed: agent_costs[resource],
+                    remaining: remaining[resource],
+                });
+            }
+        }
+        let task_costs = self.spec.task_resource_costs(agent, task);
+        if let Some(remaining) = self.task_resources.get(task) {
+            if let Some(resource) = first_exceeded(task_costs, remaining) {
+                return Err(GapError::TaskResourceExceeded {
+                    task: *task,
+                    resource,
+                    needed: task_costs[resource],
+                    remaining: remaining[resource],
+                });
+            }
         }
-        // Update assigned and budgets
+        // Update assigned, budgets and resources
         tasks.insert(*task);
         *agent_budget -= agent_spent;
         *task_budget -= task_spent;
+        if let Some(remaining) = self.agent_resources.get_mut(agent) {
+            for (remaining, cost) in remaining.iter_mut().zip(agent_costs) {
+                *remaining -= *cost;
+            }
+        }
+        if let Some(remaining) = self.task_resources.get_mut(task) {
+            for (remaining, cost) in remaining.iter_mut().zip(task_costs) {
+                *remaining -= *cost;
+            }
+        }
 
         // Update profit
         let profit = self.spec.profit(agent, task);
         self.profit += profit;
+        *self.agent_profits.entry(*agent).or_insert_with(P::zero) += profit;
 
         Ok(())
     }
@@ -113,10 +227,82 @@ where
     pub fn task_budget(&self, task: &T) -> C {
         self.task_budgets[task]
     }
+    /// Get the remaining amount of every resource of the given agent.
+    /// The slice is empty if the agent has no resources.
+    pub fn agent_resources(&self, agent: &A) -> &[C] {
+        self.agent_resources
+            .get(agent)
+            .map_or(&[], |r| r.as_slice())
+    }
+    /// Get the remaining amount of every resource of the given task.
+    /// The slice is empty if the task has no resources.
+    pub fn task_resources(&self, task: &T) -> &[C] {
+        self.task_resources.get(task).map_or(&[], |r| r.as_slice())
+    }
+    /// Check whether the given pair fits the remaining resources of its agent and task,
+    /// does not conflict with the tasks of the agent, and does not share the task
+    /// with an incompatible agent. This does not check the budgets.
+    pub(crate) fn fits_constraints(&self, agent: &A, task: &T) -> bool {
+        self.fits_resources(agent, task)
+            && !(self.spec.has_conflicts()
+                && self.agent_tasks(agent).is_some_and(|tasks| {
+                    self.spec.conflicting_tasks(task).any(|t| tasks.contains(t))
+                }))
+            && !self
+                .spec
+                .incompatible_agents(agent)
+                .any(|other| self.agent_tasks(other).is_some_and(|ts| ts.contains(task)))
+    }
+    /// Check whether the given pair fits the remaining resources of its agent and task.
+    fn fits_resources(&self, agent: &A, task: &T) -> bool {
+        let fits = |remaining: Option<&Vec<C>>, costs: &[C]| match remaining {
+            None => true,
+            Some(remaining) => first_exceeded(costs, remaining).is_none(),
+        };
+        !self.spec.has_resources()
+            || fits(
+                self.agent_resources.get(agent),
+                self.spec.agent_resource_costs(agent, task),
+            ) && fits(
+                self.task_resources.get(task),
+                self.spec.task_resource_costs(agent, task),
+            )
+    }
+    /// Check whether every agent has spent its entire budget, up to its tolerance.
+    pub fn is_complete(&self) -> bool {
+        self.agent_budgets
+            .iter()
+            .all(|(agent, budget)| *budget <= self.spec.agent_tolerance(agent))
+    }
+    /// Check whether every agent and every task has spent at least its minimum budget.
+    pub fn meets_minimums(&self) -> bool {
+        self.agent_budgets.iter().all(|(agent, budget)| {
+            *budget + self.spec.agent_minimum(agent) <= self.spec.agent_budgets()[agent]
+        }) && self.task_budgets.iter().all(|(task, budget)| {
+            *budget + self.spec.task_minimum(task) <= self.spec.task_budgets()[task]
+        })
+    }
+    /// Check whether paired agents are assigned to the same tasks.
+    pub fn meets_pairings(&self) -> bool {
+        self.assigned.iter().all(|(agent, tasks)| {
+            tasks.is_empty()
+                || self.spec.paired_agents(agent).all(|other| {
+                    self.agent_tasks(other)
+                        .is_some_and(|others| tasks.is_subset(others))
+                })
+        })
+    }
     /// Get the remaining budget of the given task.
     pub fn profit(&self) -> P {
         self.profit
     }
+    /// Get the profit of the tasks assigned to the given agent.
+    pub fn agent_profit(&self, agent: &A) -> P {
+        self.agent_profits
+            .get(agent)
+            .copied()
+            .unwrap_or_else(P::zero)
+    }
 }
 
 /// Only the assignment of agents to tasks matters here;
@@ -173,6 +359,7 @@ where
             .field("agent_budgets", &self.agent_budgets)
             .field("task_budgets", &self.task_budgets)
             .field("profit", &self.profit)
+            .field("agent_profits", &self.agent_profits)
             .finish()
     }
 }
@@ -0,0 +1,514 @@
+use crate::assignment::Assignment;
+use crate::simplex::LinearProgram;
+use crate::solver::follows;
+use crate::spec::GapSpec;
+use num::{Num, ToPrimitive};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+use std::sync::Arc;
+
+/// Relative tolerance used when comparing floating point bounds to profits.
+const TOLERANCE: f64 = 1e-9;
+
+/// Compute an optimistic estimate of the gain that any completion of the
+/// given assignment can reach, adding only pairs that follow the last added pair.
+/// The gain is the profit when maximizing, and its negation when minimizing.
+///
+/// Two relaxations of the remaining problem overestimate the remaining gain;
+/// the tightest is used. The first treats each agent as a fractional knapsack
+/// over the tasks it can still take, and does the same for each task.
+/// The second is the linear relaxation of the remaining problem.
+/// Both ignore that a finished assignment cannot be extended any further.
+///
+/// The linear relaxation is much more expensive to solve, so it is only solved
+/// if `linear` is set, in which case its dual values replace the given ones.
+/// Otherwise, it is bounded with the given dual values instead of the first
+/// relaxation, which is only used if there are none.
+pub(crate) fn upper_bound<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    duals: &mut Option<Arc<Duals<A, T>>>,
+    linear: bool,
+) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let current = gain(spec, assignment.profit());
+    upper_bound_by(
+        assignment,
+        spec,
+        last,
+        current,
+        duals,
+        linear,
+        |agent, task| gain(spec, spec.profit(agent, task)),
+    )
+}
+
+/// Compute an optimistic estimate of the total gain that any completion of the
+/// given assignment can reach, for the given current gain and gain of every pair.
+pub(crate) fn upper_bound_by<A, T, C, P, F>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    current: f64,
+    duals: &mut Option<Arc<Duals<A, T>>>,
+    linear: bool,
+    pair_gain: F,
+) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+    F: Fn(&A, &T) -> f64,
+{
+    let items = remaining_items(assignment, spec, last, pair_gain);
+    let remaining = if items.is_empty() {
+        0.0
+    } else if linear {
+        let (bound, linear_duals) = linear_bound(assignment, &items);
+        *duals = Some(Arc::new(linear_duals));
+        knapsack_bound(assignment, &items).min(bound)
+    } else {
+        match duals {
+            Some(duals) => dual_bound(assignment, &items, duals),
+            None => knapsack_bound(assignment, &items),
+        }
+    };
+    current + remaining
+}
+
+/// Compute an optimistic estimate of the fairness that any completion of the
+/// given assignment can reach, adding only pairs that follow the last added pair.
+/// The fairness is the lowest gain of any single agent.
+///
+/// Every agent is treated as a fractional knapsack over the tasks it can still take,
+/// ignoring that agents compete for the same tasks.
+pub(crate) fn fairness_bound<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let mut agent_items: HashMap<A, Vec<(f64, f64)>> = HashMap::new();
+    let items = remaining_items(assignment, spec, last, |agent, task| {
+        gain(spec, spec.profit(agent, task))
+    });
+    for item in items {
+        agent_items
+            .entry(item.agent)
+            .or_default()
+            .push((item.gain, item.agent_cost));
+    }
+    spec.agents()
+        .iter()
+        .map(|agent| {
+            let remaining = agent_items.get_mut(agent).map_or(0.0, |items| {
+                fractional_knapsack(to_f64(assignment.agent_budget(agent)), items)
+            });
+            gain(spec, assignment.agent_profit(agent)) + remaining
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Check whether every agent can still spend its budget, up to its tolerance,
+/// if complete assignments are required, whether every agent and task can
+/// still reach its minimum, and whether paired agents can still be assigned to
+/// each other's tasks, adding only pairs that follow the last added pair.
+/// This ignores that pairs may compete for the same budget.
+pub(crate) fn can_complete<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+) -> bool
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    spec.agents().iter().all(|agent| {
+        let budget = assignment.agent_budget(agent);
+        let spendable = spendable(assignment, spec, last, agent);
+        (!spec.complete() || budget <= spendable + spec.agent_tolerance(agent))
+            && budget + spec.agent_minimum(agent) <= spendable + spec.agent_budgets()[agent]
+    }) && spec.tasks().iter().all(|task| {
+        spec.task_minimum(task).is_zero()
+            || assignment.task_budget(task) + spec.task_minimum(task)
+                <= receivable(assignment, spec, last, task) + spec.task_budgets()[task]
+    }) && assignment.assigned().iter().all(|(agent, tasks)| {
+        spec.paired_agents(agent).all(|other| {
+            let assigned = assignment.agent_tasks(other);
+            tasks.iter().all(|task| {
+                assigned.is_some_and(|ts| ts.contains(task))
+                    || spec.is_eligible(other, task) && can_add(assignment, spec, last, other, task)
+            })
+        })
+    })
+}
+
+/// Compute a lower bound on the total budget that any completion of the given
+/// assignment leaves unspent, adding only pairs that follow the last added pair.
+/// This ignores that pairs may compete for the same budget.
+pub(crate) fn leftover_bound<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    spec.agents()
+        .iter()
+        .map(|agent| {
+            let budget = to_f64(assignment.agent_budget(agent));
+            (budget - to_f64(spendable(assignment, spec, last, agent))).max(0.0)
+        })
+        .sum()
+}
+
+/// Sum the agent costs of all pairs of the given agent that can still be added,
+/// following the last added pair.
+fn spendable<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    agent: &A,
+) -> C
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    spec.eligible_tasks(agent)
+        .filter(|task| can_add(assignment, spec, last, agent, task))
+        .fold(C::zero(), |total, task| {
+            total + spec.agent_cost(agent, task)
+        })
+}
+
+/// Sum the task costs of all pairs of the given task that can still be added,
+/// following the last added pair.
+fn receivable<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    task: &T,
+) -> C
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    spec.agents()
+        .iter()
+        .filter(|agent| {
+            spec.is_eligible(agent, task) && can_add(assignment, spec, last, agent, task)
+        })
+        .fold(C::zero(), |total, agent| {
+            total + spec.task_cost(agent, task)
+        })
+}
+
+/// Check whether the given eligible pair can still be added to the given assignment,
+/// following the last added pair.
+fn can_add<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    agent: &A,
+    task: &T,
+) -> bool
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    follows(last, (*agent, *task))
+        && !assignment
+            .agent_tasks(agent)
+            .is_some_and(|ts| ts.contains(task))
+        && spec.agent_cost(agent, task) <= assignment.agent_budget(agent)
+        && spec.task_cost(agent, task) <= assignment.task_budget(task)
+        && assignment.fits_constraints(agent, task)
+}
+
+/// The dual values of the budget constraints of a linear relaxation,
+/// for every agent (`Ok`) and every task (`Err`) involved.
+pub(crate) type Duals<A, T> = HashMap<Result<A, T>, f64>;
+
+/// An agent-task pair that could still increase the gain of an assignment.
+struct Item<A, T> {
+    agent: A,
+    task: T,
+    gain: f64,
+    agent_cost: f64,
+    task_cost: f64,
+}
+
+/// Collect all pairs that can still be added to the given assignment,
+/// and that would increase its gain according to the given gain of every pair.
+fn remaining_items<A, T, C, P, F>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    pair_gain: F,
+) -> Vec<Item<A, T>>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+    F: Fn(&A, &T) -> f64,
+{
+    let mut items = Vec::new();
+    for agent in spec.agents() {
+        if last.is_some_and(|(last_agent, _)| *agent < last_agent) {
+            continue;
+        }
+        let assigned = assignment.agent_tasks(agent);
+        for task in spec.eligible_tasks(agent) {
+            if !follows(last, (*agent, *task)) || assigned.is_some_and(|ts| ts.contains(task)) {
+                continue;
+            }
+            let agent_cost = spec.agent_cost(agent, task);
+            let task_cost = spec.task_cost(agent, task);
+            if agent_cost > assignment.agent_budget(agent)
+                || task_cost > assignment.task_budget(task)
+                || !assignment.fits_constraints(agent, task)
+            {
+                continue;
+            }
+            let gain = pair_gain(agent, task);
+            if gain > 0.0 {
+                items.push(Item {
+                    agent: *agent,
+                    task: *task,
+                    gain,
+                    agent_cost: to_f64(agent_cost),
+                    task_cost: to_f64(task_cost),
+                });
+            }
+        }
+    }
+    items
+}
+
+/// Bound the remaining gain by solving a fractional knapsack problem for
+/// every agent separately, and for every task separately.
+fn knapsack_bound<A, T, C, P>(assignment: &Assignment<A, T, C, P>, items: &[Item<A, T>]) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let mut agent_items: HashMap<A, Vec<(f64, f64)>> = HashMap::new();
+    let mut task_items: HashMap<T, Vec<(f64, f64)>> = HashMap::new();
+    for item in items {
+        agent_items
+            .entry(item.agent)
+            .or_default()
+            .push((item.gain, item.agent_cost));
+        task_items
+            .entry(item.task)
+            .or_default()
+            .push((item.gain, item.task_cost));
+    }
+    let agent_side: f64 = agent_items
+        .iter_mut()
+        .map(|(agent, items)| fractional_knapsack(to_f64(assignment.agent_budget(agent)), items))
+        .sum();
+    let task_side: f64 = task_items
+        .iter_mut()
+        .map(|(task, items)| fractional_knapsack(to_f64(assignment.task_budget(task)), items))
+        .sum();
+    agent_side.min(task_side)
+}
+
+/// Bound the remaining gain by the linear relaxation of the remaining problem,
+/// in which agents may be assigned to any fraction of a task.
+/// Also returns the dual values of its solution.
+fn linear_bound<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    items: &[Item<A, T>],
+) -> (f64, Duals<A, T>)
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    // One budget constraint for every agent and every task involved
+    let mut rows: HashMap<Result<A, T>, usize> = HashMap::new();
+    let mut budgets = Vec::new();
+    for item in items {
+        rows.entry(Ok(item.agent)).or_insert_with(|| {
+            budgets.push(to_f64(assignment.agent_budget(&item.agent)));
+            budgets.len() - 1
+        });
+        rows.entry(Err(item.task)).or_insert_with(|| {
+            budgets.push(to_f64(assignment.task_budget(&item.task)));
+            budgets.len() - 1
+        });
+    }
+    let mut program = LinearProgram::new(budgets);
+    for item in items {
+        let column = [
+            (rows[&Ok(item.agent)], item.agent_cost),
+            (rows[&Err(item.task)], item.task_cost),
+        ];
+        program.add_variable(item.gain, 1.0, &column);
+    }
+    let (bound, duals) = program.upper_bound();
+    let duals = rows.into_iter().map(|(row, i)| (row, duals[i])).collect();
+    (bound, duals)
+}
+
+/// Bound the remaining gain by the linear relaxation of the remaining problem,
+/// without solving it. By weak duality, any non-negative dual values of its
+/// constraints give a bound, and those of a similar problem give a tight one.
+fn dual_bound<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    items: &[Item<A, T>],
+    duals: &Duals<A, T>,
+) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let dual = |row| duals.get(&row).copied().unwrap_or(0.0);
+    let mut rows = HashSet::new();
+    let mut bound = 0.0;
+    for item in items {
+        let (agent_dual, task_dual) = (dual(Ok(item.agent)), dual(Err(item.task)));
+        if rows.insert(Ok(item.agent)) {
+            bound += agent_dual * to_f64(assignment.agent_budget(&item.agent));
+        }
+        if rows.insert(Err(item.task)) {
+            bound += task_dual * to_f64(assignment.task_budget(&item.task));
+        }
+        // Every variable is at most one
+        bound += (item.gain - agent_dual * item.agent_cost - task_dual * item.task_cost).max(0.0);
+    }
+    bound
+}
+
+/// Determine whether a node with the given bounds can no longer reach the given scores.
+/// Scores are compared lexicographically: a later score only matters when
+/// the earlier bounds cannot exceed the earlier scores.
+pub(crate) fn is_dominated(bounds: &[f64], scores: &[f64]) -> bool {
+    for (bound, score) in bounds.iter().zip(scores) {
+        let tolerance = TOLERANCE * (1.0 + score.abs());
+        if bound + tolerance < *score {
+            return true;
+        }
+        if *bound > score + tolerance {
+            return false;
+        }
+    }
+    false
+}
+
+/// Determine whether a node with the given bounds may still beat the given scores.
+/// Scores are compared lexicographically, as for `is_dominated`.
+pub(crate) fn can_exceed(bounds: &[f64], scores: &[f64]) -> bool {
+    for (bound, score) in bounds.iter().zip(scores) {
+        let tolerance = TOLERANCE * (1.0 + score.abs());
+        if *bound > score + tolerance {
+            return true;
+        }
+        if bound + tolerance < *score {
+            return false;
+        }
+    }
+    false
+}
+
+/// Determine whether a node with the given bounds can only reach profit vectors that
+/// are dominated by the given profit vector: no better in any dimension, and worse in one.
+pub(crate) fn is_pareto_dominated(bounds: &[f64], values: &[f64]) -> bool {
+    let mut worse = false;
+    for (bound, value) in bounds.iter().zip(values) {
+        let tolerance = TOLERANCE * (1.0 + value.abs());
+        if *bound > value + tolerance {
+            return false;
+        }
+        worse |= bound + tolerance < *value;
+    }
+    worse
+}
+
+/// Solve the fractional knapsack problem for the given (profit, cost) items.
+fn fractional_knapsack(capacity: f64, items: &mut [(f64, f64)]) -> f64 {
+    // Sort items by decreasing profit per unit of cost; free items come first
+    items.sort_by(|x, y| ratio(y).partial_cmp(&ratio(x)).unwrap_or(Ordering::Equal));
+
+    let mut remaining = capacity;
+    let mut total = 0.0;
+    for &(profit, cost) in items.iter() {
+        if cost <= remaining {
+            remaining -= cost;
+            total += profit;
+        } else {
+            total += profit * remaining / cost;
+            break;
+        }
+    }
+    total
+}
+
+fn ratio(item: &(f64, f64)) -> f64 {
+    if item.1 > 0.0 {
+        item.0 / item.1
+    } else {
+        f64::INFINITY
+    }
+}
+
+/// Compute the gap between an upper bound and the profit of an assignment,
+/// relative to that profit. Profits smaller than one in absolute value are
+/// divided by one instead, so the gap of an assignment without profit is finite.
+pub(crate) fn relative_gap(bound: f64, profit: f64) -> f64 {
+    let difference = bound - profit;
+    if difference <= TOLERANCE * (1.0 + profit.abs()) {
+        0.0
+    } else {
+        difference / profit.abs().max(1.0)
+    }
+}
+
+/// Convert a profit into a gain, according to the sense of the given spec.
+pub(crate) fn gain<A, T, C, P>(spec: &GapSpec<A, T, C, P>, profit: P) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + PartialOrd + Copy,
+    P: Num + PartialOrd + Copy + ToPrimitive,
+{
+    spec.sense().gain(to_f64(profit))
+}
+
+pub(crate) fn to_f64<N: ToPrimitive>(n: N) -> f64 {
+    n.to_f64().expect("Value cannot be represented as a float.")
+}
@@ -0,0 +1,8 @@
+/// Tells the solver whether to keep searching after reporting an assignment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Control {
+    /// Keep searching for better assignments.
+    Continue,
+    /// Stop searching, and return the best assignments found so far.
+    Stop,
+}
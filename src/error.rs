@@ -0,0 +1,337 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Debug, Display};
+
+/// The result of an operation that can fail with a `GapError`.
+pub type GapResult<R, A, T, C> = Result<R, GapError<A, T, C>>;
+
+/// An error raised while specifying, assigning or solving an assignment problem.
+///
+/// The variants fall into three groups, by the operation that raises them:
+/// - `Assignment::assign` returns the errors of assigning an agent to a task,
+///   from `DuplicateAssignment` to `IncompatibleAgents`.
+/// - `GapSpec::validate` returns `InvalidSpec`, which holds the inconsistencies
+///   from `ForbiddenPair` to `InconsistentResources`, and the assignment errors of
+///   the pre-assigned and required pairs. Every solver returns it for an inconsistent
+///   spec. `GapSpec::set_objectives` returns `NoObjectives`.
+/// - The solvers return the remaining variants. `solve` returns `Infeasible`,
+///   `solve_pool` returns `EmptyPool`, and `solve_pareto` returns the errors of
+///   profit vectors. `solve_knapsack` and `solve_lagrangian` return the variants
+///   from `NonIntegerBudget` to `KnapsackTooLarge` for specs they cannot solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GapError<A, T, C> {
+    // Assigning
+    /// The agent is already assigned to the task.
+    DuplicateAssignment {
+        /// The agent that was assigned.
+        agent: A,
+        /// The task the agent was assigned to.
+        task: T,
+    },
+    /// The agent is not part of the spec.
+    UnknownAgent(A),
+    /// The task is not part of the spec.
+    UnknownTask(T),
+    /// The agent may not be assigned to the task.
+    IneligiblePair {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The agent does not have enough budget left for the task.
+    AgentBudgetExceeded {
+        /// The agent that was assigned.
+        agent: A,
+        /// The cost of the task for the agent.
+        needed: C,
+        /// The budget the agent has left.
+        remaining: C,
+    },
+    /// The task does not have enough budget left for the agent.
+    TaskBudgetExceeded {
+        /// The task that was assigned.
+        task: T,
+        /// The cost of the agent for the task.
+        needed: C,
+        /// The budget the task has left.
+        remaining: C,
+    },
+    /// The agent does not have enough of one of its resources left for the task.
+    AgentResourceExceeded {
+        /// The agent that was assigned.
+        agent: A,
+        /// The index of the resource.
+        resource: usize,
+        /// The cost of the task for the agent in the resource.
+        needed: C,
+        /// The amount of the resource the agent has left.
+        remaining: C,
+    },
+    /// The task does not have enough of one of its resources left for the agent.
+    TaskResourceExceeded {
+        /// The task that was assigned.
+        task: T,
+        /// The index of the resource.
+        resource: usize,
+        /// The cost of the agent for the task in the resource.
+        needed: C,
+        /// The amount of the resource the task has left.
+        remaining: C,
+    },
+    /// The agent already holds a task that conflicts with the task.
+    ConflictingTasks {
+        /// The agent that was assigned.
+        agent: A,
+        /// The task the agent was assigned to.
+        task: T,
+        /// The task of the agent it conflicts with.
+        conflict: T,
+    },
+    /// The task is already held by an agent that may not share a task with the agent.
+    IncompatibleAgents {
+        /// The agent that was assigned.
+        agent: A,
+        /// The task the agent was assigned to.
+        task: T,
+        /// The agent holding the task.
+        other: A,
+    },
+
+    // Validating
+    /// The pair is forbidden, but pre-assigned or required.
+    ForbiddenPair {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The agent has no budget.
+    MissingAgentBudget(A),
+    /// The task has no budget.
+    MissingTaskBudget(T),
+    /// The agent-task pair has no agent cost.
+    MissingAgentCost {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The agent-task pair has no task cost.
+    MissingTaskCost {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The agent-task pair has no profit.
+    MissingProfit {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The agents or tasks differ in their number of resources,
+    /// or some resource cost vector does not match that number.
+    InconsistentResources,
+    /// The spec is inconsistent; holds every inconsistency found by `GapSpec::validate`.
+    InvalidSpec(Vec<GapError<A, T, C>>),
+    /// The spec was given no objectives to rank assignments by.
+    NoObjectives,
+
+    // Solving
+    /// No finished assignment satisfies the constraints of the spec.
+    Infeasible,
+    /// The pool was given a size of zero, so it can never hold an assignment.
+    EmptyPool,
+    /// The agent-task pair has no profit vector.
+    MissingProfitVector {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The profit vectors are empty, or differ in length.
+    InconsistentProfitVectors,
+    /// The budget of the agent is not a non-negative integer, as the solver requires.
+    NonIntegerBudget(A),
+    /// The agent cost of the agent-task pair is not a non-negative integer,
+    /// as the solver requires.
+    NonIntegerCost {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The profit of the agent-task pair is not positive, as the solver requires.
+    NonPositiveProfit {
+        /// The agent of the pair.
+        agent: A,
+        /// The task of the pair.
+        task: T,
+    },
+    /// The budget of the task cannot cover every agent that fits it,
+    /// while the solver requires task budgets never to bind.
+    SharedTaskBudget(T),
+    /// The spec has a sense, constraints or objectives the solver does not support.
+    UnsupportedSpec,
+    /// The knapsack problem of the agent is too large to solve by dynamic programming.
+    KnapsackTooLarge(A),
+}
+
+impl<A, T, C> Display for GapError<A, T, C>
+where
+    A: Debug,
+    T: Debug,
+    C: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GapError::DuplicateAssignment { agent, task } => {
+                write!(
+                    f,
+                    "Agent {:?} is already assigned to task {:?}.",
+                    agent, task
+                )
+            }
+            GapError::UnknownAgent(agent) => {
+                write!(f, "Agent {:?} not present in configuration.", agent)
+            }
+            GapError::UnknownTask(task) => {
+                write!(f, "Task {:?} not present in configuration.", task)
+            }
+            GapError::IneligiblePair { agent, task } => write!(
+                f,
+                "Agent {:?} may not be assigned to task {:?}.",
+                agent, task
+            ),
+            GapError::AgentBudgetExceeded {
+                agent,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "Agent {:?} needs a budget of {:?}, but has {:?} left.",
+                agent, needed, remaining
+            ),
+            GapError::TaskBudgetExceeded {
+                task,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "Task {:?} needs a budget of {:?}, but has {:?} left.",
+                task, needed, remaining
+            ),
+            GapError::AgentResourceExceeded {
+                agent,
+                resource,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "Agent {:?} needs {:?} of resource {}, but has {:?} left.",
+                agent, needed, resource, remaining
+            ),
+            GapError::TaskResourceExceeded {
+                task,
+                resource,
+                needed,
+                remaining,
+            } => write!(
+                f,
+                "Task {:?} needs {:?} of resource {}, but has {:?} left.",
+                task, needed, resource, remaining
+            ),
+            GapError::ConflictingTasks {
+                agent,
+                task,
+                conflict,
+            } => write!(
+                f,
+                "Agent {:?} cannot take task {:?}, as it conflicts with task {:?}.",
+                agent, task, conflict
+            ),
+            GapError::IncompatibleAgents { agent, task, other } => write!(
+                f,
+                "Agent {:?} cannot share task {:?} with agent {:?}.",
+                agent, task, other
+            ),
+            GapError::ForbiddenPair { agent, task } => write!(
+                f,
+                "Agent {:?} is forbidden from task {:?}, but must be assigned to it.",
+                agent, task
+            ),
+            GapError::MissingAgentBudget(agent) => write!(f, "Agent {:?} has no budget.", agent),
+            GapError::MissingTaskBudget(task) => write!(f, "Task {:?} has no budget.", task),
+            GapError::MissingAgentCost { agent, task } => write!(
+                f,
+                "Agent {:?} has no agent cost for task {:?}.",
+                agent, task
+            ),
+            GapError::MissingTaskCost { agent, task } => {
+                write!(f, "Agent {:?} has no task cost for task {:?}.", agent, task)
+            }
+            GapError::MissingProfit { agent, task } => {
+                write!(f, "Agent {:?} has no profit for task {:?}.", agent, task)
+            }
+            GapError::InconsistentResources => write!(
+                f,
+                "Resource vectors must have the same length for all agents and for all tasks."
+            ),
+            GapError::InvalidSpec(errors) => {
+                write!(f, "Invalid spec:")?;
+                for error in errors {
+                    write!(f, " {}", error)?;
+                }
+                Ok(())
+            }
+            GapError::NoObjectives => write!(f, "At least one objective is required."),
+            GapError::Infeasible => {
+                write!(f, "No assignment satisfies the constraints of the spec.")
+            }
+            GapError::EmptyPool => write!(f, "The pool must be able to hold an assignment."),
+            GapError::MissingProfitVector { agent, task } => write!(
+                f,
+                "Agent {:?} has no profit vector for task {:?}.",
+                agent, task
+            ),
+            GapError::InconsistentProfitVectors => {
+                write!(f, "Profit vectors must all have the same, positive length.")
+            }
+            GapError::NonIntegerBudget(agent) => {
+                write!(f, "Budget of agent {:?} is not an integer.", agent)
+            }
+            GapError::NonIntegerCost { agent, task } => write!(
+                f,
+                "Cost of agent {:?} for task {:?} is not an integer.",
+                agent, task
+            ),
+            GapError::NonPositiveProfit { agent, task } => write!(
+                f,
+                "Profit of agent {:?} for task {:?} is not positive.",
+                agent, task
+            ),
+            GapError::SharedTaskBudget(task) => {
+                write!(f, "Budget of task {:?} is shared between agents.", task)
+            }
+            GapError::UnsupportedSpec => write!(
+                f,
+                "The spec has a sense, constraints or objectives the solver does not support."
+            ),
+            GapError::KnapsackTooLarge(agent) => write!(
+                f,
+                "Knapsack problem of agent {:?} is too large to solve by dynamic programming.",
+                agent
+            ),
+        }
+    }
+}
+
+impl<A, T, C> Error for GapError<A, T, C>
+where
+    A: Debug,
+    T: Debug,
+    C: Debug,
+{
+}
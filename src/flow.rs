@@ -0,0 +1,380 @@
+use crate::assignment::Assignment;
+use crate::bound::to_f64;
+use crate::control::Control;
+use crate::knapsack::to_units;
+use crate::solver::Progress;
+use crate::spec::{GapSpec, Sense};
+use num::{Num, ToPrimitive};
+use std::collections::VecDeque;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Relative tolerance used when comparing path costs.
+const TOLERANCE: f64 = 1e-9;
+
+/// Check whether the given spec can be solved as a min-cost flow problem:
+/// every cost equals one, every budget is a whole number, and every profit is positive
+/// and maximized.
+///
+/// Positive profits ensure that every maximum flow assignment is finished,
+/// as any pair that could still be added would increase the profit.
+pub(crate) fn applies<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> bool
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + PartialOrd + Copy + ToPrimitive,
+    P: Num + PartialOrd + Copy,
+{
+    !spec.has_side_constraints()
+        && !spec.has_side_objectives()
+        && spec.sense() == Sense::Maximize
+        && spec
+            .agent_budgets()
+            .values()
+            .all(|b| to_units(*b).is_some())
+        && spec.task_budgets().values().all(|b| to_units(*b).is_some())
+        && spec.agents().iter().all(|a| {
+            spec.eligible_tasks(a).all(|t| {
+                spec.agent_cost(a, t).is_one()
+                    && spec.task_cost(a, t).is_one()
+                    && spec.profit(a, t) > P::zero()
+            })
+        })
+}
+
+/// Solve the assignment problem as a min-cost flow problem,
+/// and enumerate all maximum assignments until a limit is reached.
+/// Every assignment is reported as soon as it is found,
+/// and the enumeration stops early if the report returns `Control::Stop`.
+///
+/// Flow runs from a source through the agents and tasks to a sink. The capacities
+/// of the source and sink edges are the agent and task budgets, and every
+/// agent-task edge carries at most one unit of flow at a cost of minus its profit.
+pub(crate) fn solve<'a, A, T, C, P, F>(
+    spec: &'a GapSpec<A, T, C, P>,
+    progress: &mut Progress,
+    report: &mut F,
+) -> Control
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+    F: FnMut(Assignment<'a, A, T, C, P>, &Progress) -> Control,
+{
+    let start = Assignment::from_spec(spec);
+    let mut agents: Vec<A> = spec.agents().iter().copied().collect();
+    let mut tasks: Vec<T> = spec.tasks().iter().copied().collect();
+    agents.sort();
+    tasks.sort();
+
+    // Nodes: source, sink, agents, tasks
+    let (source, sink) = (0, 1);
+    let agent_node = |i: usize| 2 + i;
+    let task_node = |j: usize| 2 + agents.len() + j;
+    let mut network = Network::new(2 + agents.len() + tasks.len());
+    for (i, a) in agents.iter().enumerate() {
+        let budget = to_units(start.agent_budget(a)).unwrap();
+        network.add_edge(source, agent_node(i), budget, 0.0);
+    }
+    for (j, t) in tasks.iter().enumerate() {
+        let budget = to_units(start.task_budget(t)).unwrap();
+        network.add_edge(task_node(j), sink, budget, 0.0);
+    }
+    let mut pairs = Vec::new();
+    for (i, a) in agents.iter().enumerate() {
+        for (j, t) in tasks.iter().enumerate() {
+            if !spec.is_eligible(a, t) || start.agent_tasks(a).is_some_and(|ts| ts.contains(t)) {
+                continue;
+            }
+            let profit = to_f64(spec.profit(a, t));
+            let edge = network.add_edge(agent_node(i), task_node(j), 1, -profit);
+            pairs.push((edge, *a, *t));
+        }
+    }
+    let scale = pairs
+        .iter()
+        .fold(1.0, |m: f64, (e, _, _)| m.max(network.edges[*e].cost.abs()));
+    network.tolerance = TOLERANCE * scale;
+
+    network.maximize_profit(source, sink);
+    // A return edge lets the enumeration change the amount of flow as well
+    network.add_edge(sink, source, usize::MAX, 0.0);
+    let potentials = network.potentials();
+
+    // Enumerate all optimal flows; agent-task edges may be fixed to their current flow
+    let mut fixed = vec![false; network.edges.len()];
+    let candidates: Vec<usize> = pairs.iter().map(|(e, _, _)| *e).collect();
+    let mut found = 0;
+    network.enumerate(
+        &potentials,
+        &candidates,
+        &mut fixed,
+        &mut found,
+        progress,
+        &mut |edges, progress| {
+            let mut assignment = start.clone();
+            for (e, a, t) in &pairs {
+                if edges[*e].flow > 0 {
+                    assignment.assign_fitting(a, t);
+                }
+            }
+            report(assignment, progress)
+        },
+    )
+}
+
+/// A directed edge in a flow network.
+#[derive(Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    capacity: usize,
+    cost: f64,
+    flow: usize,
+}
+
+/// An edge of the residual network: an edge index, and whether it is used forwards.
+type Arc = (usize, bool);
+
+/// A flow network with integer capacities and real costs.
+#[derive(Debug)]
+struct Network {
+    nodes: usize,
+    edges: Vec<Edge>,
+    adjacent: Vec<Vec<usize>>,
+    tolerance: f64,
+}
+
+impl Network {
+    fn new(nodes: usize) -> Self {
+        Self {
+            nodes,
+            edges: Vec::new(),
+            adjacent: vec![Vec::new(); nodes],
+            tolerance: TOLERANCE,
+        }
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, capacity: usize, cost: f64) -> usize {
+        self.edges.push(Edge {
+            from,
+            to,
+            capacity,
+            cost,
+            flow: 0,
+        });
+        let index = self.edges.len() - 1;
+        self.adjacent[from].push(index);
+        self.adjacent[to].push(index);
+        index
+    }
+
+    /// Get the residual arcs leaving the given node, along with their head and cost.
+    fn arcs(&self, node: usize) -> impl Iterator<Item = (Arc, usize, f64)> + '_ {
+        self.adjacent[node].iter().filter_map(move |&e| {
+            let edge = &self.edges[e];
+            if edge.from == node && edge.flow < edge.capacity {
+                Some(((e, true), edge.to, edge.cost))
+            } else if edge.to == node && edge.flow > 0 {
+                Some(((e, false), edge.from, -edge.cost))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn push(&mut self, (e, forward): Arc, amount: usize) {
+        if forward {
+            self.edges[e].flow += amount;
+        } else {
+            self.edges[e].flow -= amount;
+        }
+    }
+
+    /// Find the cheapest path in the residual network with Bellman-Ford.
+    fn shortest_paths(&self, from: usize) -> (Vec<f64>, Vec<Option<Arc>>) {
+        let mut distance = vec![f64::INFINITY; self.nodes];
+        let mut previous = vec![None; self.nodes];
+        let mut queued = vec![false; self.nodes];
+        let mut queue = VecDeque::new();
+        distance[from] = 0.0;
+        queue.push_back(from);
+        while let Some(node) = queue.pop_front() {
+            queued[node] = false;
+            for (arc, head, cost) in self.arcs(node) {
+                if distance[node] + cost < distance[head] - self.tolerance {
+                    distance[head] = distance[node] + cost;
+                    previous[head] = Some(arc);
+                    if !queued[head] {
+                        queued[head] = true;
+                        queue.push_back(head);
+                    }
+                }
+            }
+        }
+        (distance, previous)
+    }
+
+    /// Send flow from source to sink along successive cheapest paths,
+    /// for as long as this decreases the total cost.
+    fn maximize_profit(&mut self, source: usize, sink: usize) {
+        loop {
+            let (distance, previous) = self.shortest_paths(source);
+            if distance[sink] >= -self.tolerance {
+                break;
+            }
+            let mut path = Vec::new();
+            let mut node = sink;
+            while node != source {
+                let arc = previous[node].unwrap();
+                let edge = &self.edges[arc.0];
+                node = if arc.1 { edge.from } else { edge.to };
+                path.push(arc);
+            }
+            let amount = path
+                .iter()
+                .map(|&(e, forward)| {
+                    let edge = &self.edges[e];
+                    if forward {
+                        edge.capacity - edge.flow
+                    } else {
+                        edge.flow
+                    }
+                })
+                .min()
+                .unwrap();
+            for arc in path {
+                self.push(arc, amount);
+            }
+        }
+    }
+
+    /// Compute node potentials for which no residual arc has a negative reduced cost.
+    /// These exist because the residual network of an optimal flow has no negative cycles.
+    fn potentials(&self) -> Vec<f64> {
+        let mut potential = vec![0.0; self.nodes];
+        for _ in 0..self.nodes {
+            let mut changed = false;
+            for node in 0..self.nodes {
+                for (_, head, cost) in self.arcs(node) {
+                    if potential[node] + cost < potential[head] - self.tolerance {
+                        potential[head] = potential[node] + cost;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        potential
+    }
+
+    /// Find a cycle of tight residual arcs through the given arc, avoiding fixed edges.
+    /// Such a cycle changes the flow without changing its cost.
+    fn tight_cycle(&self, potentials: &[f64], fixed: &[bool], arc: Arc) -> Option<Vec<Arc>> {
+        let edge = &self.edges[arc.0];
+        let (tail, head) = if arc.1 {
+            (edge.from, edge.to)
+        } else {
+            (edge.to, edge.from)
+        };
+        // Breadth-first search from the head of the arc back to its tail
+        let mut previous: Vec<Option<Arc>> = vec![None; self.nodes];
+        let mut visited = vec![false; self.nodes];
+        let mut queue = VecDeque::new();
+        visited[head] = true;
+        queue.push_back(head);
+        while let Some(node) = queue.pop_front() {
+            if node == tail {
+                let mut cycle = vec![arc];
+                let mut current = tail;
+                while current != head {
+                    let arc = previous[current].unwrap();
+                    let edge = &self.edges[arc.0];
+                    current = if arc.1 { edge.from } else { edge.to };
+                    cycle.push(arc);
+                }
+                return Some(cycle);
+            }
+            for (next_arc, next, cost) in self.arcs(node) {
+                let reduced = cost + potentials[node] - potentials[next];
+                if !visited[next] && !fixed[next_arc.0] && reduced.abs() <= self.tolerance {
+                    visited[next] = true;
+                    previous[next] = Some(next_arc);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Enumerate all optimal flows that agree with the current flow on the fixed edges.
+    ///
+    /// Pick a candidate edge whose flow can change without changing the cost.
+    /// The optimal flows then split into those where the edge keeps its flow,
+    /// and those where it does not; both are enumerated with the edge fixed.
+    /// The enumeration stops once a limit is reached. Every flow is reported
+    /// as soon as it is found, and counted as found.
+    fn enumerate<F>(
+        &mut self,
+        potentials: &[f64],
+        candidates: &[usize],
+        fixed: &mut [bool],
+        found: &mut usize,
+        progress: &mut Progress,
+        report: &mut F,
+    ) -> Control
+    where
+        F: FnMut(&[Edge], &Progress) -> Control,
+    {
+        if progress.limit_reached(*found) {
+            return Control::Continue;
+        }
+        progress.count_node();
+        let mut branch = None;
+        for &e in candidates {
+            if fixed[e] {
+                continue;
+            }
+            let edge = &self.edges[e];
+            let arc = (e, edge.flow == 0);
+            let reduced = edge.cost + potentials[edge.from] - potentials[edge.to];
+            if reduced.abs() > self.tolerance {
+                continue;
+            }
+            if let Some(cycle) = self.tight_cycle(potentials, fixed, arc) {
+                branch = Some((e, cycle));
+                break;
+            }
+        }
+        match branch {
+            None => {
+                *found += 1;
+                report(&self.edges, progress)
+            }
+            Some((e, cycle)) => {
+                fixed[e] = true;
+                if self.enumerate(potentials, candidates, fixed, found, progress, report)
+                    == Control::Stop
+                {
+                    return Control::Stop;
+                }
+                for &arc in &cycle {
+                    self.push(arc, 1);
+                }
+                if self.enumerate(potentials, candidates, fixed, found, progress, report)
+                    == Control::Stop
+                {
+                    return Control::Stop;
+                }
+                for &(e, forward) in &cycle {
+                    self.push((e, !forward), 1);
+                }
+                fixed[e] = false;
+                Control::Continue
+            }
+        }
+    }
+}
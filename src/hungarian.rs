@@ -0,0 +1,237 @@
+use crate::assignment::Assignment;
+use crate::bound::gain;
+use crate::control::Control;
+use crate::solver::Progress;
+use crate::spec::GapSpec;
+use num::{Num, ToPrimitive};
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Relative tolerance used when testing whether an edge is tight.
+const TOLERANCE: f64 = 1e-9;
+
+/// Check whether the given spec describes a linear assignment problem:
+/// every agent may do every task, and every budget and every cost equals one,
+/// so each agent does at most one task, and each task is done by at most one agent.
+pub(crate) fn applies<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> bool
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + PartialOrd + Copy,
+    P: Num + PartialOrd + Copy,
+{
+    !spec.has_side_constraints()
+        && !spec.has_side_objectives()
+        && spec.agent_budgets().values().all(|b| b.is_one())
+        && spec.task_budgets().values().all(|b| b.is_one())
+        && spec.agents().iter().all(|a| {
+            spec.eligible_tasks(a).count() == spec.tasks().len()
+                && spec
+                    .eligible_tasks(a)
+                    .all(|t| spec.agent_cost(a, t).is_one() && spec.task_cost(a, t).is_one())
+        })
+}
+
+/// Solve a linear assignment problem with the Hungarian method,
+/// and enumerate all best assignments until a limit is reached.
+/// Every assignment is reported as soon as it is found,
+/// and the enumeration stops early if the report returns `Control::Stop`.
+///
+/// As every agent can do every task, an assignment is finished once either
+/// all free agents or all free tasks are assigned. The maximum assignments
+/// are therefore exactly the best weight matchings of that size.
+pub(crate) fn solve<'a, A, T, C, P, F>(
+    spec: &'a GapSpec<A, T, C, P>,
+    progress: &mut Progress,
+    report: &mut F,
+) -> Control
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+    F: FnMut(Assignment<'a, A, T, C, P>, &Progress) -> Control,
+{
+    let start = Assignment::from_spec(spec);
+    let mut agents: Vec<A> = spec
+        .agents()
+        .iter()
+        .copied()
+        .filter(|a| !start.agent_budget(a).is_zero())
+        .collect();
+    let mut tasks: Vec<T> = spec
+        .tasks()
+        .iter()
+        .copied()
+        .filter(|t| !start.task_budget(t).is_zero())
+        .collect();
+    agents.sort();
+    tasks.sort();
+
+    // The Hungarian method requires at most as many rows as columns
+    let transpose = agents.len() > tasks.len();
+    let (rows, cols) = if transpose {
+        (tasks.len(), agents.len())
+    } else {
+        (agents.len(), tasks.len())
+    };
+    let pair = |i: usize, j: usize| {
+        if transpose {
+            (agents[j], tasks[i])
+        } else {
+            (agents[i], tasks[j])
+        }
+    };
+    // Maximizing the gain is minimizing its negation
+    let cost: Vec<Vec<f64>> = (0..rows)
+        .map(|i| {
+            (0..cols)
+                .map(|j| {
+                    let (a, t) = pair(i, j);
+                    -gain(spec, spec.profit(&a, &t))
+                })
+                .collect()
+        })
+        .collect();
+
+    let (u, v) = potentials(&cost, rows, cols);
+    let scale = cost.iter().flatten().fold(1.0, |m: f64, c| m.max(c.abs()));
+    let tolerance = TOLERANCE * scale;
+
+    // Enumerate all matchings using tight edges only
+    let tight: Vec<Vec<usize>> = (0..rows)
+        .map(|i| {
+            (0..cols)
+                .filter(|&j| (cost[i][j] - u[i] - v[j]).abs() <= tolerance)
+                .collect()
+        })
+        .collect();
+    let required: Vec<bool> = v.iter().map(|v| *v < -tolerance).collect();
+    let mut current = Vec::with_capacity(rows);
+    let mut used = vec![false; cols];
+    let mut found = 0;
+    enumerate(
+        &tight,
+        &required,
+        &mut used,
+        &mut current,
+        &mut found,
+        progress,
+        &mut |matching, progress| {
+            let mut assignment = start.clone();
+            for (i, &j) in matching.iter().enumerate() {
+                let (a, t) = pair(i, j);
+                assignment.assign_fitting(&a, &t);
+            }
+            report(assignment, progress)
+        },
+    )
+}
+
+/// Run the Hungarian method on the given cost matrix, with at most as many rows as columns.
+/// Returns optimal dual potentials for the rows and columns. Every column
+/// with a negative potential is matched in every minimum cost matching.
+fn potentials(cost: &[Vec<f64>], rows: usize, cols: usize) -> (Vec<f64>, Vec<f64>) {
+    // Index zero is used as a sentinel for rows and columns
+    let mut u = vec![0.0; rows + 1];
+    let mut v = vec![0.0; cols + 1];
+    let mut matched = vec![0; cols + 1];
+    let mut way = vec![0; cols + 1];
+    for i in 1..=rows {
+        matched[0] = i;
+        let mut j0 = 0;
+        let mut min_slack = vec![f64::INFINITY; cols + 1];
+        let mut used = vec![false; cols + 1];
+        loop {
+            used[j0] = true;
+            let i0 = matched[j0];
+            let mut delta = f64::INFINITY;
+            let mut j1 = 0;
+            for j in 1..=cols {
+                if !used[j] {
+                    let slack = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                    if slack < min_slack[j] {
+                        min_slack[j] = slack;
+                        way[j] = j0;
+                    }
+                    if min_slack[j] < delta {
+                        delta = min_slack[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..=cols {
+                if used[j] {
+                    u[matched[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if matched[j0] == 0 {
+                break;
+            }
+        }
+        // Augment along the alternating path
+        loop {
+            let j1 = way[j0];
+            matched[j0] = matched[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+    (u[1..].to_vec(), v[1..].to_vec())
+}
+
+/// Enumerate all matchings of every row to a distinct column along the given
+/// edges, covering all required columns, until a limit is reached.
+/// Every matching is reported as soon as it is found, and counted as found.
+fn enumerate<F>(
+    edges: &[Vec<usize>],
+    required: &[bool],
+    used: &mut [bool],
+    current: &mut Vec<usize>,
+    found: &mut usize,
+    progress: &mut Progress,
+    report: &mut F,
+) -> Control
+where
+    F: FnMut(&[usize], &Progress) -> Control,
+{
+    if progress.limit_reached(*found) {
+        return Control::Continue;
+    }
+    progress.count_node();
+    let row = current.len();
+    if row == edges.len() {
+        if required.iter().zip(used.iter()).all(|(r, u)| !r || *u) {
+            *found += 1;
+            return report(current, progress);
+        }
+        return Control::Continue;
+    }
+    // Stop if the remaining rows cannot cover the remaining required columns
+    let uncovered = required
+        .iter()
+        .zip(used.iter())
+        .filter(|(r, u)| **r && !**u)
+        .count();
+    if uncovered > edges.len() - row {
+        return Control::Continue;
+    }
+    for &col in &edges[row] {
+        if !used[col] {
+            used[col] = true;
+            current.push(col);
+            if enumerate(edges, required, used, current, found, progress, report) == Control::Stop {
+                return Control::Stop;
+            }
+            current.pop();
+            used[col] = false;
+        }
+    }
+    Control::Continue
+}
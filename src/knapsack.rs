@@ -0,0 +1,337 @@
+use crate::assignment::Assignment;
+use crate::control::Control;
+use crate::error::{GapError, GapResult};
+use crate::options::SolveOptions;
+use crate::solver::{Assignments, Progress};
+use crate::spec::{GapSpec, Sense};
+use num::{Num, ToPrimitive};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Dynamic programming is only used if no table exceeds this many entries.
+const MAX_TABLE_SIZE: usize = 1 << 22;
+
+/// A 0/1 knapsack problem with integer weights, solved by dynamic programming.
+///
+/// The table holds, for every item and every capacity, the maximum value that
+/// can be reached using only that item and the items after it.
+#[derive(Debug)]
+pub(crate) struct Knapsack<V> {
+    capacity: usize,
+    weights: Vec<usize>,
+    values: Vec<V>,
+    table: Vec<V>,
+}
+
+impl<V> Knapsack<V>
+where
+    V: Num + PartialOrd + Copy,
+{
+    /// Solve the knapsack problem for the given (weight, value) items.
+    /// The table takes time and memory proportional to the number of items
+    /// times the capacity.
+    pub(crate) fn new(capacity: usize, items: &[(usize, V)]) -> Self {
+        let width = capacity + 1;
+        let mut table = vec![V::zero(); (items.len() + 1) * width];
+        for (i, &(weight, value)) in items.iter().enumerate().rev() {
+            for w in 0..width {
+                let skip = table[(i + 1) * width + w];
+                table[i * width + w] = if weight <= w {
+                    let take = value + table[(i + 1) * width + w - weight];
+                    if take > skip {
+                        take
+                    } else {
+                        skip
+                    }
+                } else {
+                    skip
+                };
+            }
+        }
+        Self {
+            capacity,
+            weights: items.iter().map(|item| item.0).collect(),
+            values: items.iter().map(|item| item.1).collect(),
+            table,
+        }
+    }
+
+    /// Get the maximum value that fits in the knapsack.
+    pub(crate) fn value(&self) -> V {
+        self.best(0, self.capacity)
+    }
+
+    /// Get the indices of the items in a maximum packing.
+    pub(crate) fn solution(&self) -> Vec<usize> {
+        let mut chosen = Vec::new();
+        let mut w = self.capacity;
+        for i in 0..self.weights.len() {
+            if self.weights[i] <= w
+                && self.values[i] + self.best(i + 1, w - self.weights[i]) == self.best(i, w)
+            {
+                chosen.push(i);
+                w -= self.weights[i];
+            }
+        }
+        chosen
+    }
+
+    /// Get the indices of the items in every maximum packing, until a limit is reached.
+    /// At least one maximum packing is returned.
+    pub(crate) fn solutions(&self, progress: &mut Progress) -> Vec<Vec<usize>> {
+        let mut solutions = Vec::new();
+        let mut chosen = Vec::new();
+        self.collect(0, self.capacity, &mut chosen, &mut solutions, progress);
+        if solutions.is_empty() {
+            solutions.push(self.solution());
+        }
+        solutions
+    }
+
+    /// Collect the maximum packings using only the given item and the items after it,
+    /// until a limit is reached.
+    fn collect(
+        &self,
+        item: usize,
+        capacity: usize,
+        chosen: &mut Vec<usize>,
+        solutions: &mut Vec<Vec<usize>>,
+        progress: &mut Progress,
+    ) {
+        if progress.limit_reached(solutions.len()) {
+            return;
+        }
+        progress.count_node();
+        if item == self.weights.len() {
+            solutions.push(chosen.clone());
+            return;
+        }
+        let best = self.best(item, capacity);
+        let weight = self.weights[item];
+        if weight <= capacity && self.values[item] + self.best(item + 1, capacity - weight) == best
+        {
+            chosen.push(item);
+            self.collect(item + 1, capacity - weight, chosen, solutions, progress);
+            chosen.pop();
+        }
+        if self.best(item + 1, capacity) == best {
+            self.collect(item + 1, capacity, chosen, solutions, progress);
+        }
+    }
+
+    /// Get the maximum value using only the given item and the items after it.
+    fn best(&self, item: usize, capacity: usize) -> V {
+        self.table[item * (self.capacity + 1) + capacity]
+    }
+}
+
+/// Check whether the table of a knapsack problem with the given number of items
+/// and capacity is small enough to solve it by dynamic programming.
+pub(crate) fn fits_table(items: usize, capacity: usize) -> bool {
+    (items + 1).saturating_mul(capacity.saturating_add(1)) <= MAX_TABLE_SIZE
+}
+
+/// Convert a cost or budget to a whole number of units, if it is a non-negative integer.
+pub(crate) fn to_units<C: ToPrimitive>(c: C) -> Option<usize> {
+    let f = c.to_f64()?;
+    if f >= 0.0 && f.fract() == 0.0 {
+        c.to_usize()
+    } else {
+        None
+    }
+}
+
+/// Solve the assignment problem specified in the given spec
+/// as a separate knapsack problem for every agent.
+///
+/// This is possible if the task budgets never bind: every task can be done by
+/// all agents that fit its budget at once. This always holds for a single agent.
+/// The knapsack problems are solved exactly by dynamic programming, and
+/// all maximum assignments are returned.
+///
+/// Agent costs and budgets must be non-negative integers, every pair that fits
+/// the budgets must have a positive profit, and the profit must be maximized.
+/// The table of every agent must be small enough to hold in memory.
+/// Constraints beyond the budgets and objectives other than the profit coming first
+/// are not supported. An error is returned otherwise, or if the spec is inconsistent.
+/// Ties in profit are not broken by the remaining objectives.
+pub fn solve_knapsack<A, T, C, P>(
+    spec: &GapSpec<A, T, C, P>,
+) -> GapResult<Assignments<'_, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    spec.validate()?;
+    let mut assignments = Assignments::new();
+    solve(
+        spec,
+        &mut Progress::new(&SolveOptions::new()),
+        &mut |assignment, _| {
+            assignments.insert(assignment);
+            Control::Continue
+        },
+    )?;
+    Ok(assignments)
+}
+
+/// Solve the given spec as a separate knapsack problem for every agent,
+/// and combine their maximum packings until a limit is reached.
+/// Once it is, the assignment being combined is completed with the first
+/// maximum packing of every remaining agent.
+/// Every assignment is reported as soon as it is combined,
+/// and the combination stops early if the report returns `Control::Stop`.
+pub(crate) fn solve<'a, A, T, C, P, F>(
+    spec: &'a GapSpec<A, T, C, P>,
+    progress: &mut Progress,
+    report: &mut F,
+) -> GapResult<Control, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy,
+    F: FnMut(Assignment<'a, A, T, C, P>, &Progress) -> Control,
+{
+    let start = Assignment::from_spec(spec);
+    let mut packings = Vec::new();
+    for (agent, capacity, items) in agent_items(&start, spec)? {
+        if !fits_table(items.len(), capacity) {
+            return Err(GapError::KnapsackTooLarge(agent));
+        }
+        let weights: Vec<(usize, P)> = items.iter().map(|item| (item.1, item.2)).collect();
+        let solutions = Knapsack::new(capacity, &weights).solutions(progress);
+        let tasks: Vec<Vec<T>> = solutions
+            .into_iter()
+            .map(|solution| solution.into_iter().map(|i| items[i].0).collect())
+            .collect();
+        packings.push((agent, tasks));
+    }
+    Ok(combine(start, &packings, &mut 0, progress, report))
+}
+
+/// Complete the given assignment with every combination of the given maximum packings
+/// of the remaining agents, until a limit is reached. Every finished assignment is
+/// reported and counted as found.
+fn combine<'a, A, T, C, P, F>(
+    assignment: Assignment<'a, A, T, C, P>,
+    packings: &[(A, Vec<Vec<T>>)],
+    found: &mut usize,
+    progress: &mut Progress,
+    report: &mut F,
+) -> Control
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+    F: FnMut(Assignment<'a, A, T, C, P>, &Progress) -> Control,
+{
+    let ((agent, tasks), remaining) = match packings.split_first() {
+        Some(first) => first,
+        None => {
+            *found += 1;
+            return report(assignment, progress);
+        }
+    };
+    for packing in tasks {
+        let mut next = assignment.clone();
+        for task in packing {
+            next.assign_fitting(agent, task);
+        }
+        if combine(next, remaining, found, progress, report) == Control::Stop {
+            return Control::Stop;
+        }
+        // Once a limit is reached, the remaining packings of this agent are skipped
+        if progress.limit_reached(*found) {
+            break;
+        }
+    }
+    Control::Continue
+}
+
+/// Check whether the given spec splits into knapsack problems
+/// that are small enough to solve by dynamic programming.
+pub(crate) fn applies<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> bool
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    let start = Assignment::from_spec(spec);
+    match agent_items(&start, spec) {
+        Ok(knapsacks) => knapsacks
+            .iter()
+            .all(|(_, capacity, items)| fits_table(items.len(), *capacity)),
+        Err(_) => false,
+    }
+}
+
+/// The knapsack problem of a single agent:
+/// the agent, its capacity, and the (task, weight, value) items it can take.
+type AgentItems<A, T, P> = (A, usize, Vec<(T, usize, P)>);
+
+/// Collect the knapsack problem of every agent, in order.
+/// Returns an error if the problem does not split into knapsack problems.
+fn agent_items<A, T, C, P>(
+    start: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+) -> GapResult<Vec<AgentItems<A, T, P>>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    if spec.sense() == Sense::Minimize || spec.has_side_constraints() || spec.has_side_objectives()
+    {
+        return Err(GapError::UnsupportedSpec);
+    }
+    let mut agents: Vec<A> = spec.agents().iter().copied().collect();
+    let mut tasks: Vec<T> = spec.tasks().iter().copied().collect();
+    agents.sort();
+    tasks.sort();
+
+    let mut knapsacks = Vec::new();
+    let mut demand: Vec<C> = vec![C::zero(); tasks.len()];
+    for agent in agents {
+        let budget = start.agent_budget(&agent);
+        let capacity = to_units(budget).ok_or(GapError::NonIntegerBudget(agent))?;
+        let mut items = Vec::new();
+        for (task, demand) in tasks.iter().zip(demand.iter_mut()) {
+            if !spec.is_eligible(&agent, task) {
+                continue;
+            }
+            let agent_cost = spec.agent_cost(&agent, task);
+            let task_cost = spec.task_cost(&agent, task);
+            if start
+                .agent_tasks(&agent)
+                .is_some_and(|ts| ts.contains(task))
+                || agent_cost > budget
+                || task_cost > start.task_budget(task)
+            {
+                continue;
+            }
+            let weight =
+                to_units(agent_cost).ok_or(GapError::NonIntegerCost { agent, task: *task })?;
+            let profit = spec.profit(&agent, task);
+            if profit.partial_cmp(&P::zero()) != Some(Ordering::Greater) {
+                return Err(GapError::NonPositiveProfit { agent, task: *task });
+            }
+            *demand = *demand + task_cost;
+            items.push((*task, weight, profit));
+        }
+        knapsacks.push((agent, capacity, items));
+    }
+    for (task, demand) in tasks.iter().zip(demand) {
+        if demand > start.task_budget(task) {
+            return Err(GapError::SharedTaskBudget(*task));
+        }
+    }
+    Ok(knapsacks)
+}
@@ -0,0 +1,294 @@
+use crate::assignment::Assignment;
+use crate::bound::{gain, relative_gap, to_f64};
+use crate::error::{GapError, GapResult};
+use crate::knapsack::{fits_table, to_units, Knapsack};
+use crate::spec::{GapSpec, Sense};
+use log::{debug, info, trace};
+use num::{Num, ToPrimitive};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Number of iterations without improvement of the bound before the step size is halved.
+const PATIENCE: usize = 10;
+/// The search stops once the step size drops below this value.
+const MIN_STEP_SIZE: f64 = 1e-4;
+
+/// The result of the Lagrangian relaxation heuristic.
+/// Holds a feasible assignment, and a bound on the best profit.
+#[derive(Debug)]
+pub struct LagrangianSolution<'a, A, T, C, P> {
+    assignment: Assignment<'a, A, T, C, P>,
+    upper_bound: f64,
+    iterations: usize,
+    sense: Sense,
+}
+
+impl<'a, A, T, C, P> LagrangianSolution<'a, A, T, C, P> {
+    /// Get the best feasible assignment found.
+    pub fn assignment(&self) -> &Assignment<'a, A, T, C, P> {
+        &self.assignment
+    }
+    /// Take ownership of the best feasible assignment found.
+    pub fn into_assignment(self) -> Assignment<'a, A, T, C, P> {
+        self.assignment
+    }
+    /// Get a bound on the best profit of any assignment: an upper bound
+    /// when maximizing, and a lower bound when minimizing.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+    /// Get the number of subgradient iterations performed.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+impl<'a, A, T, C, P> LagrangianSolution<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    /// Get the difference between the bound and the profit of the assignment,
+    /// relative to that profit, or to one if the profit is smaller than that in
+    /// absolute value. This certifies how far the assignment is from optimal.
+    pub fn gap(&self) -> f64 {
+        relative_gap(
+            self.sense.gain(self.upper_bound),
+            self.sense.gain(to_f64(self.assignment.profit())),
+        )
+    }
+}
+
+/// Approximate the assignment problem specified in the given spec
+/// using Lagrangian relaxation.
+///
+/// The task budget constraints are relaxed, which splits the problem into
+/// a knapsack problem for every agent. The multipliers of the relaxed constraints
+/// are improved by subgradient optimisation for at most the given number of iterations.
+/// Each iteration yields a bound on the best profit, and the knapsack
+/// solutions are repaired into a feasible assignment. At least one iteration
+/// is performed, so the bound is always finite.
+///
+/// The knapsack problems are solved by dynamic programming, so agent costs and
+/// budgets must be non-negative integers, the table of every agent must be small
+/// enough to hold in memory, and constraints beyond the budgets are not supported.
+/// An error is returned otherwise, or if the spec is inconsistent.
+pub fn solve_lagrangian<A, T, C, P>(
+    spec: &GapSpec<A, T, C, P>,
+    iterations: usize,
+) -> GapResult<LagrangianSolution<'_, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    spec.validate()?;
+    if spec.has_side_constraints() || spec.has_side_objectives() {
+        return Err(GapError::UnsupportedSpec);
+    }
+    let start = Assignment::from_spec(spec);
+    let pairs = remaining_pairs(&start, spec)?;
+    let mut agents: Vec<A> = spec.agents().iter().copied().collect();
+    let mut tasks: Vec<T> = spec.tasks().iter().copied().collect();
+    agents.sort();
+    tasks.sort();
+    for agent in &agents {
+        let items = pairs.iter().filter(|pair| pair.agent == *agent).count();
+        let capacity = to_units(start.agent_budget(agent)).unwrap_or(0);
+        if !fits_table(items, capacity) {
+            return Err(GapError::KnapsackTooLarge(*agent));
+        }
+    }
+
+    let start_gain = gain(spec, start.profit());
+    let mut multipliers: HashMap<T, f64> = tasks.iter().map(|t| (*t, 0.0)).collect();
+    let mut best = repair(&start, spec, &pairs, &[]);
+    let mut lower_bound = gain(spec, best.profit());
+    let mut upper_bound = f64::INFINITY;
+    let mut step_size = 2.0;
+    let mut stalled = 0;
+    let mut iteration = 0;
+
+    while iteration < iterations.max(1) {
+        iteration += 1;
+
+        // Solve a knapsack problem for every agent, with penalized profits
+        let mut bound = start_gain;
+        let mut chosen: Vec<&Pair<A, T>> = Vec::new();
+        for agent in &agents {
+            let candidates: Vec<(&Pair<A, T>, f64)> = pairs
+                .iter()
+                .filter(|pair| pair.agent == *agent)
+                .map(|pair| (pair, pair.gain - multipliers[&pair.task] * pair.task_cost))
+                .filter(|(_, value)| *value > 0.0)
+                .collect();
+            let items: Vec<(usize, f64)> = candidates
+                .iter()
+                .map(|(pair, value)| (pair.agent_cost, *value))
+                .collect();
+            let capacity = to_units(start.agent_budget(agent)).unwrap_or(0);
+            let knapsack = Knapsack::new(capacity, &items);
+            bound += knapsack.value();
+            chosen.extend(knapsack.solution().into_iter().map(|i| candidates[i].0));
+        }
+        for task in &tasks {
+            bound += multipliers[task] * to_f64(start.task_budget(task));
+        }
+
+        // Keep the best bound
+        if bound < upper_bound - 1e-9 {
+            upper_bound = bound;
+            stalled = 0;
+        } else {
+            stalled += 1;
+            if stalled >= PATIENCE {
+                step_size /= 2.0;
+                stalled = 0;
+            }
+        }
+
+        // Repair the relaxed solution into a feasible assignment
+        let repaired = repair(&start, spec, &pairs, &chosen);
+        if gain(spec, repaired.profit()) > lower_bound {
+            lower_bound = gain(spec, repaired.profit());
+            info!("Found new best assignment -- {}", repaired);
+            best = repaired;
+        }
+        trace!(
+            "Iteration {} -- bound: {} - best: {} - step size: {}",
+            iteration,
+            bound,
+            lower_bound,
+            step_size
+        );
+        if relative_gap(upper_bound, lower_bound) == 0.0 || step_size < MIN_STEP_SIZE {
+            break;
+        }
+
+        // Determine the subgradient: the amount by which each task budget is exceeded
+        let mut subgradient: HashMap<T, f64> = tasks
+            .iter()
+            .map(|t| (*t, -to_f64(start.task_budget(t))))
+            .collect();
+        for pair in &chosen {
+            *subgradient.get_mut(&pair.task).unwrap() += pair.task_cost;
+        }
+        // Only the directions that can move the multipliers matter
+        let norm: f64 = tasks
+            .iter()
+            .filter(|t| subgradient[t] > 0.0 || multipliers[t] > 0.0)
+            .map(|t| subgradient[t] * subgradient[t])
+            .sum();
+        if norm == 0.0 {
+            // The relaxed solution satisfies all task budgets and is therefore optimal
+            break;
+        }
+
+        // Move the multipliers along the subgradient
+        let step = step_size * (bound - lower_bound) / norm;
+        for task in &tasks {
+            let multiplier = multipliers.get_mut(task).unwrap();
+            *multiplier = (*multiplier + step * subgradient[task]).max(0.0);
+        }
+    }
+    debug!(
+        "Lagrangian relaxation finished after {} iterations -- bound: {} - best: {}",
+        iteration, upper_bound, best
+    );
+    Ok(LagrangianSolution {
+        assignment: best,
+        upper_bound: spec.sense().gain(upper_bound.max(lower_bound)),
+        iterations: iteration,
+        sense: spec.sense(),
+    })
+}
+
+/// An agent-task pair that can still be added to the initial assignment.
+#[derive(Debug)]
+struct Pair<A, T> {
+    agent: A,
+    task: T,
+    gain: f64,
+    agent_cost: usize,
+    task_cost: f64,
+}
+
+/// Collect the pairs that can still be added to the given assignment.
+fn remaining_pairs<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+) -> GapResult<Vec<Pair<A, T>>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let mut pairs = Vec::new();
+    for agent in spec.agents() {
+        if to_units(assignment.agent_budget(agent)).is_none() {
+            return Err(GapError::NonIntegerBudget(*agent));
+        }
+        for task in spec.eligible_tasks(agent) {
+            if assignment
+                .agent_tasks(agent)
+                .is_some_and(|tasks| tasks.contains(task))
+            {
+                continue;
+            }
+            let agent_cost =
+                to_units(spec.agent_cost(agent, task)).ok_or(GapError::NonIntegerCost {
+                    agent: *agent,
+                    task: *task,
+                })?;
+            pairs.push(Pair {
+                agent: *agent,
+                task: *task,
+                gain: gain(spec, spec.profit(agent, task)),
+                agent_cost,
+                task_cost: to_f64(spec.task_cost(agent, task)),
+            });
+        }
+    }
+    Ok(pairs)
+}
+
+/// Turn the pairs chosen by the relaxation into a feasible assignment.
+///
+/// Chosen pairs are added in order of decreasing gain, skipping those that
+/// exceed a task budget. The assignment is then completed greedily with the
+/// remaining pairs.
+fn repair<'a, A, T, C, P>(
+    start: &Assignment<'a, A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    pairs: &[Pair<A, T>],
+    chosen: &[&Pair<A, T>],
+) -> Assignment<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    let mut assignment = start.clone();
+    let mut chosen = chosen.to_vec();
+    let mut rest: Vec<&Pair<A, T>> = pairs.iter().collect();
+    for pairs in [&mut chosen, &mut rest] {
+        pairs.sort_by(|x, y| y.gain.partial_cmp(&x.gain).unwrap_or(Ordering::Equal));
+        for pair in pairs.iter() {
+            if spec.agent_cost(&pair.agent, &pair.task) <= assignment.agent_budget(&pair.agent)
+                && spec.task_cost(&pair.agent, &pair.task) <= assignment.task_budget(&pair.task)
+            {
+                // Fails only if the pair was already added
+                let _ = assignment.assign(&pair.agent, &pair.task);
+            }
+        }
+    }
+    assignment
+}
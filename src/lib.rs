@@ -44,22 +44,409 @@ let assigned = [("a", vec!["1"])];
 spec.set_assigned(assigned);
 
 // Let the solving algorithm work its magic!
-let assignments = solve(&spec);
+let assignments = solve(&spec).unwrap();
 
 // This problem specification will result in a single maximum assignment
 let assigned = [("a", vec!["1"]), ("b", vec!["1", "2"]), ("c", vec!["2"])];
 assert_eq!(assignments.len(), 1);
 assert!(assignments.contains(&Assignment::from_assigned(assigned, &spec)));
 ```
+
+# Sparse problems
+
+When agents may only do a few of the tasks, create the spec with `GapSpec::new_sparse`
+and list the allowed agent-task pairs. Costs and profits only need to be set for
+these pairs, and the solver never considers any other pair.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let pairs = [("a", "1"), ("b", "1"), ("b", "2")];
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new_sparse(["a", "b"], ["1", "2"], pairs);
+spec.set_profits([(("a", "1"), 2), (("b", "1"), 2), (("b", "2"), 1)]);
+
+// Agent b takes task 2, leaving task 1 to agent a, which cannot do anything else
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().profit(), 3);
+```
+
+# Forbidden and required pairs
+
+Use `set_forbidden` to declare pairs that may never be assigned, for example
+because of a conflict of interest, and `set_required` to declare pairs that must
+be part of every assignment. Required pairs are assigned before solving, like
+pre-assigned pairs. If they do not fit the budgets, or are forbidden as well,
+the solver returns an error naming every budget and pair at fault.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_profits([(("a", "1"), 3), (("a", "2"), 1), (("b", "1"), 1), (("b", "2"), 3)]);
+spec.set_forbidden([("b", "2")]);
+spec.set_required([("b", "1")]);
+
+// Agent a takes the remaining task
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().profit(), 2);
+```
+
+# Conflicting tasks
+
+Use `set_conflicts` to declare groups of tasks that no agent may combine, for example
+because they overlap in time. An agent is assigned to at most one task of every group,
+no matter how much budget it has left.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a"], ["1", "2", "3"]);
+spec.set_agent_budgets([("a", 3)]);
+spec.set_profits([(("a", "1"), 2), (("a", "2"), 2), (("a", "3"), 1)]);
+spec.set_conflicts([["1", "2"]]);
+
+// The agent takes task 3, and either task 1 or task 2
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 2);
+assert!(result.iter().all(|assignment| assignment.profit() == 3));
+```
+
+# Incompatible and paired agents
+
+When tasks have room for several agents, use `set_incompatible` to declare pairs of
+agents that may never share a task, and `set_paired` to declare pairs of agents that
+must share every task: whenever one of them is assigned to a task, so is the other.
+Like minimums, pairings are checked on finished assignments.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b", "c"], ["1", "2"]);
+spec.set_task_budgets([("1", 2), ("2", 2)]);
+spec.set_profits([
+    (("a", "1"), 3), (("a", "2"), 1),
+    (("b", "1"), 3), (("b", "2"), 1),
+    (("c", "1"), 1), (("c", "2"), 0),
+]);
+spec.set_incompatible([("a", "b")]);
+spec.set_paired([("a", "c")]);
+
+// Agents a and b cannot both take task 1, and agent c goes wherever agent a goes
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().profit(), 5);
+```
+
+# Multiple resources
+
+Besides their budget, agents and tasks can have any number of other resources.
+Use `set_agent_resources` and `set_task_resources` to give every agent or every task
+a vector with the amount of each resource, and `set_agent_resource_costs` and
+`set_task_resource_costs` to set what every pair costs in each of them.
+A pair only fits if it fits every resource, and pairs without a cost vector cost nothing.
+Specs with resources are always searched, as the solvers for special cases do not
+support them.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a"], ["1", "2"]);
+spec.set_agent_budgets([("a", 2)]);
+spec.set_profits([(("a", "1"), 1), (("a", "2"), 2)]);
+
+// Besides its time, the agent has a single tool that both tasks need
+spec.set_agent_resources([("a", vec![1])]);
+spec.set_agent_resource_costs([(("a", "1"), vec![1]), (("a", "2"), vec![1])]);
+
+let result = solve(&spec).unwrap();
+let assignment = result.iter().next().unwrap();
+assert_eq!(assignment.profit(), 2);
+assert_eq!(assignment.agent_resources(&"a"), &[0]);
+```
+
+# Validation
+
+The solvers validate the spec before solving, and return a `GapError` if it is
+inconsistent: for example, when a pair has no profit, a budget is set for an agent
+that is not part of the spec, or the pre-assignments exceed the budgets.
+Call `validate` on the spec to get every inconsistency at once.
+
+```
+use gap_solver::{solve, GapError, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a"], ["1"]);
+spec.set_agent_budgets([("a", 1), ("b", 1)]);
+spec.set_profits([]);
+
+let errors = vec![
+    GapError::MissingProfit { agent: "a", task: "1" },
+    GapError::UnknownAgent("b"),
+];
+assert_eq!(spec.validate(), Err(GapError::InvalidSpec(errors.clone())));
+assert_eq!(solve(&spec), Err(GapError::InvalidSpec(errors)));
+```
+
+# Minimization
+
+By default, the assignments with the highest profit are found. When profits
+represent costs instead, set the sense of the spec to `Sense::Minimize` to find
+the finished assignments with the lowest total. This works for unsigned profit
+types as well, as no profits are negated.
+
+```
+use gap_solver::{solve, GapSpec, Sense};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_profits([(("a", "1"), 4), (("a", "2"), 1), (("b", "1"), 2), (("b", "2"), 2)]);
+spec.set_sense(Sense::Minimize);
+
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().profit(), 3);
+```
+
+# Complete assignments
+
+Call `set_complete` on the spec to only accept assignments in which every agent
+spends its entire budget. Use `set_agent_tolerances` to allow agents to leave
+some budget unspent. If no complete assignment exists, `solve` returns
+`GapError::Infeasible`, and the solution of `solve_with_options` has the
+status `SolveStatus::Infeasible` and holds no assignments.
+
+Minimums are a looser requirement: use `set_agent_minimums` to require agents to spend
+at least part of their budget, and `set_task_minimums` to require tasks to be covered.
+When task costs equal one, the minimum of a task is the number of agents it needs.
+
+```
+use gap_solver::{solve, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_task_budgets([("1", 2), ("2", 1)]);
+spec.set_profits([(("a", "1"), 1), (("a", "2"), 3), (("b", "1"), 1), (("b", "2"), 3)]);
+spec.set_task_minimums([("1", 2)]);
+
+// Both agents must cover task 1, so nobody takes the more profitable task 2
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().profit(), 2);
+```
+
+# Fairness
+
+By default, all assignments with the best profit are returned. Call `set_fairness`
+on the spec to take the profit of the worst-off agent into account: the lowest agent
+profit when maximizing, and the highest when minimizing. `Fairness::TieBreak` only
+returns the fairest of the assignments with the best profit, while `Fairness::Primary`
+returns the fairest assignments overall, breaking ties by profit. The profit of
+every agent is available through `Assignment::agent_profit`.
+
+```
+use gap_solver::{solve, Fairness, GapSpec};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_agent_budgets([("a", 2), ("b", 2)]);
+spec.set_fairness(Fairness::TieBreak);
+
+// Only the assignments in which both agents do a task remain
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 2);
+assert!(result.iter().all(|assignment| assignment.agent_profit(&"b") == 1));
+```
+
+# Multiple objectives
+
+Use `set_objectives` to rank assignments by an ordered list of objectives. Later
+objectives only decide between assignments that tie on all earlier ones. Besides the
+profit and the fairness, the solver can minimize the budget left unspent by the agents,
+or maximize a custom value computed from each finished assignment. The search prunes
+with every objective it can bound, so the ranking also holds when the search is cut off.
+
+```
+use gap_solver::{solve, GapSpec, Objective};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_agent_budgets([("a", 2), ("b", 1)]);
+spec.set_objectives([
+    Objective::Profit,
+    Objective::Custom(Box::new(|assignment| {
+        assignment.agent_tasks(&"a").map_or(0.0, |tasks| tasks.len() as f64)
+    })),
+])
+.unwrap();
+
+// Of the maximum assignments, the one giving agent a the most tasks remains
+let result = solve(&spec).unwrap();
+assert_eq!(result.len(), 1);
+assert_eq!(result.iter().next().unwrap().agent_tasks(&"a").unwrap().len(), 2);
+```
+
+# Pareto front
+
+When objectives have no fixed priority, give every agent-task pair a vector of
+profits with `set_profit_vectors`, and use `solve_pareto` to find all finished
+assignments whose summed profit vector is not dominated by that of another.
+
+```
+use gap_solver::{solve_pareto, GapSpec, SolveOptions};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_profit_vectors([
+    (("a", "1"), vec![2, 0]),
+    (("a", "2"), vec![1, 1]),
+    (("b", "1"), vec![1, 1]),
+    (("b", "2"), vec![0, 2]),
+]);
+
+// Both assignments trade off the first dimension against the second
+let solution = solve_pareto(&spec, &SolveOptions::new()).unwrap();
+assert_eq!(solution.assignments().len(), 2);
+```
+
+# Solution pools
+
+Use `solve_pool` to keep a ranked pool of good assignments instead of only the
+best ones, for instance to choose between them by criteria the model does not capture.
+`PoolOptions` bounds the pool to the `k` best assignments, to the assignments within
+a relative gap of the best one, or both. A minimum difference keeps the pool diverse,
+by requiring its assignments to differ in a number of agent-task pairs.
+
+```
+use gap_solver::{solve_pool, GapSpec, PoolOptions, SolveOptions};
+
+let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+spec.set_profits([(("a", "1"), 3), (("a", "2"), 1), (("b", "1"), 1), (("b", "2"), 1)]);
+
+let mut pool_options = PoolOptions::new();
+pool_options.set_size(2);
+
+let pool = solve_pool(&spec, &SolveOptions::new(), &pool_options).unwrap();
+let profits: Vec<u32> = pool.assignments().iter().map(|a| a.profit()).collect();
+assert_eq!(profits, vec![4, 2]);
+```
+
+# Early stopping
+
+The search space grows exponentially with the total agent and task budget.
+Use `solve_with_options` to limit the wall-clock time, the number of expanded
+nodes, or the size of the open set. A `CancellationToken` stops the search
+from another thread. The returned `Solution` holds the best
+assignments found so far, and a status telling whether these are proven optimal.
+It also reports an upper bound on the maximum profit, derived from the linear
+relaxation of the unexplored assignments, and the resulting optimality gap.
+
+```
+use gap_solver::{solve_with_options, GapSpec, SolveOptions, SolveStatus};
+use std::time::Duration;
+
+let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(["a", "b"], ["1", "2"]);
+
+let mut options = SolveOptions::new();
+options.set_time_limit(Duration::from_secs(10));
+options.set_node_limit(1_000);
+
+let solution = solve_with_options(&spec, &options).unwrap();
+assert_eq!(solution.status(), SolveStatus::Optimal);
+assert_eq!(solution.assignments().len(), 2);
+assert_eq!(solution.gap(), Some(0.0));
+```
+
+# Anytime search
+
+Use `solve_with_callback` to be notified of every new or tied maximum
+assignment while the search is still running. The callback receives the
+assignment, the elapsed time and the number of expanded nodes, and returns
+whether the search should continue.
+
+```
+use gap_solver::{solve_with_callback, Control, GapSpec, SolveOptions, SolveStatus};
+
+let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(["a", "b"], ["1", "2"]);
+
+let solution = solve_with_callback(&spec, &SolveOptions::new(), |assignment, elapsed, nodes| {
+    println!("{} after {:?} and {} nodes", assignment, elapsed, nodes);
+    Control::Continue
+}).unwrap();
+assert_eq!(solution.status(), SolveStatus::Optimal);
+```
+
+# Iterating over optimal assignments
+
+Heavily tied problems can have a huge number of maximum assignments. Use
+`solve_iter` to iterate over them instead: each assignment is yielded as soon as
+it is proven optimal, and the search only continues when the next one is requested.
+
+```
+use gap_solver::{solve_iter, GapSpec, SolveOptions};
+
+// Every one of the 120 permutations is a maximum assignment
+let agents: Vec<u32> = (0..5).collect();
+let spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), agents);
+
+let first = solve_iter(&spec, &SolveOptions::new()).unwrap().next().unwrap();
+assert_eq!(first.profit(), 5);
+```
+
+# Parallel search
+
+Use `solve_parallel` to expand nodes on several threads at once. The threads
+share the open set and the best profit found so far, and produce the same
+maximum assignments as `solve_with_options`. This requires the agent, task,
+cost and profit types to be `Send` and `Sync`.
+
+# Large problems
+
+For problems far too large to search, `solve_lagrangian` quickly produces a
+single good assignment using Lagrangian relaxation. It also reports an upper
+bound on the maximum profit, certifying how far the assignment is from optimal.
+
+```
+use gap_solver::{solve_lagrangian, GapSpec};
+
+let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(["a", "b"], ["1", "2"]);
+
+let result = solve_lagrangian(&spec, 100).unwrap();
+assert_eq!(result.assignment().profit(), 2.0);
+assert_eq!(result.gap(), 0.0);
+```
 */
 
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 
 mod assignment;
+mod bound;
+mod cancel;
+mod control;
+mod error;
+mod flow;
+mod hungarian;
+mod knapsack;
+mod lagrangian;
+mod objective;
+mod optimal;
+mod options;
+mod parallel;
+mod pareto;
+mod pool;
+mod simplex;
+mod solution;
 mod solver;
 mod spec;
 
 pub use crate::assignment::Assignment;
-pub use crate::solver::solve;
-pub use crate::spec::GapSpec;
+pub use crate::cancel::CancellationToken;
+pub use crate::control::Control;
+pub use crate::error::{GapError, GapResult};
+pub use crate::knapsack::solve_knapsack;
+pub use crate::lagrangian::{solve_lagrangian, LagrangianSolution};
+pub use crate::objective::{Objective, ObjectiveFn};
+pub use crate::optimal::{solve_iter, OptimalAssignments};
+pub use crate::options::SolveOptions;
+pub use crate::parallel::solve_parallel;
+pub use crate::pareto::solve_pareto;
+pub use crate::pool::{solve_pool, Pool, PoolOptions};
+pub use crate::solution::{Solution, SolveStatus};
+pub use crate::solver::{solve, solve_with_callback, solve_with_options};
+pub use crate::spec::{Fairness, GapSpec, Sense};
@@ -0,0 +1,115 @@
+use crate::assignment::Assignment;
+use crate::bound::{fairness_bound, gain, leftover_bound, to_f64, upper_bound, Duals};
+use crate::spec::GapSpec;
+use num::{Num, ToPrimitive};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+use std::sync::Arc;
+
+/// A function computing a custom objective value from a finished assignment.
+pub type ObjectiveFn<A, T, C, P> = Box<dyn Fn(&Assignment<'_, A, T, C, P>) -> f64 + Send + Sync>;
+
+/// A criterion by which finished assignments are ranked.
+///
+/// A spec holds a list of objectives, which are optimized lexicographically:
+/// a later objective only decides between assignments that tie on all earlier ones.
+pub enum Objective<A, T, C, P> {
+    /// The total profit, maximized or minimized according to the sense of the spec.
+    Profit,
+    /// The profit of the worst-off agent: the lowest agent profit when maximizing,
+    /// and the highest when minimizing.
+    Fairness,
+    /// The total budget left unspent by the agents, which is minimized.
+    Leftover,
+    /// A value computed from a finished assignment, which is maximized.
+    /// Negate the value to minimize it instead.
+    ///
+    /// The solver cannot bound custom values, so these never prune the search.
+    Custom(ObjectiveFn<A, T, C, P>),
+}
+
+impl<A, T, C, P> fmt::Debug for Objective<A, T, C, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Objective::Profit => write!(f, "Profit"),
+            Objective::Fairness => write!(f, "Fairness"),
+            Objective::Leftover => write!(f, "Leftover"),
+            Objective::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Score the given assignment on every objective of its spec.
+/// Scores are gains, so higher is better, and are compared lexicographically.
+pub(crate) fn score<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+) -> Vec<f64>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    spec.objectives()
+        .iter()
+        .map(|objective| match objective {
+            Objective::Profit => gain(spec, assignment.profit()),
+            Objective::Fairness => spec
+                .agents()
+                .iter()
+                .map(|agent| gain(spec, assignment.agent_profit(agent)))
+                .fold(f64::INFINITY, f64::min),
+            Objective::Leftover => -spec
+                .agents()
+                .iter()
+                .map(|agent| to_f64(assignment.agent_budget(agent)))
+                .sum::<f64>(),
+            Objective::Custom(value) => value(assignment),
+        })
+        .collect()
+}
+
+/// Bound the scores that any completion of the given assignment can reach,
+/// adding only pairs that follow the last added pair. The bound on the profit
+/// only solves its linear relaxation if `linear` is set, and otherwise bounds it
+/// with the given dual values.
+pub(crate) fn bounds<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    duals: &mut Option<Arc<Duals<A, T>>>,
+    linear: bool,
+) -> Vec<f64>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    spec.objectives()
+        .iter()
+        .map(|objective| match objective {
+            Objective::Profit => upper_bound(assignment, spec, last, duals, linear),
+            Objective::Fairness => fairness_bound(assignment, spec, last),
+            Objective::Leftover => -leftover_bound(assignment, spec, last),
+            Objective::Custom(_) => f64::INFINITY,
+        })
+        .collect()
+}
+
+/// Convert a score on the primary objective of the given spec back into its value.
+pub(crate) fn primary_value<A, T, C, P>(spec: &GapSpec<A, T, C, P>, score: f64) -> f64
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + PartialOrd + Copy,
+    P: Num + PartialOrd + Copy,
+{
+    match spec.objectives()[0] {
+        Objective::Profit | Objective::Fairness => spec.sense().gain(score),
+        Objective::Leftover => -score,
+        Objective::Custom(_) => score,
+    }
+}
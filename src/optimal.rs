@@ -0,0 +1,170 @@
+use crate::assignment::Assignment;
+use crate::bound::{can_exceed, is_dominated};
+use crate::control::Control;
+use crate::error::GapResult;
+use crate::objective::score;
+use crate::options::SolveOptions;
+use crate::solution::SolveStatus;
+use crate::solver::{Node, Progress, Search, Strategy, Verdict};
+use crate::spec::GapSpec;
+use log::info;
+use num::{Num, ToPrimitive};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Iterate over the maximum assignments of the assignment problem specified
+/// in the given spec, yielding each of them as soon as it is proven optimal.
+///
+/// Unlike `solve`, the maximum assignments are never all held in memory at once:
+/// the search only continues when the next assignment is requested. An assignment
+/// is proven optimal once no node left to explore can beat it. Problems that could be
+/// solved without searching are searched as well, as the solvers for special cases
+/// produce all maximum assignments at once.
+///
+/// Iteration ends early when one of the limits in the given options is reached;
+/// maximum assignments found but not yet proven optimal are not yielded.
+/// The status of the iterator tells why it ended. Returns an error if the spec is inconsistent.
+pub fn solve_iter<'a, A, T, C, P>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+) -> GapResult<OptimalAssignments<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    spec.validate()?;
+    let strategy = Optimal {
+        spec,
+        best_score: None,
+        pending: Vec::new(),
+        proven: false,
+    };
+    let search = Search::new(spec, Progress::new(options), &strategy);
+    Ok(OptimalAssignments { search, strategy })
+}
+
+/// An iterator over maximum assignments, created by `solve_iter`.
+pub struct OptimalAssignments<'a, A, T, C, P> {
+    search: Search<'a, A, T, C, P>,
+    strategy: Optimal<'a, A, T, C, P>,
+}
+
+/// The strategy of searching for maximum assignments one at a time.
+struct Optimal<'a, A, T, C, P> {
+    spec: &'a GapSpec<A, T, C, P>,
+    best_score: Option<Vec<f64>>,
+    /// Maximum assignments found but not yet yielded.
+    pending: Vec<Assignment<'a, A, T, C, P>>,
+    /// Whether no node left to explore can beat the best score.
+    proven: bool,
+}
+
+impl<'a, A, T, C, P> OptimalAssignments<'a, A, T, C, P> {
+    /// Get the reason the search ended, or `None` if it is still running.
+    pub fn status(&self) -> Option<SolveStatus> {
+        match self.search.progress().status() {
+            Some(SolveStatus::Optimal) if self.strategy.best_score.is_none() => {
+                Some(SolveStatus::Infeasible)
+            }
+            status => status,
+        }
+    }
+    /// Get the number of nodes expanded so far.
+    pub fn nodes_expanded(&self) -> usize {
+        self.search.progress().nodes_expanded()
+    }
+}
+
+impl<'a, A, T, C, P> Debug for OptimalAssignments<'a, A, T, C, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OptimalAssignments")
+            .field("best_score", &self.strategy.best_score)
+            .field("pending", &self.strategy.pending.len())
+            .field("proven", &self.strategy.proven)
+            .field("nodes_expanded", &self.nodes_expanded())
+            .field("status", &self.status())
+            .finish()
+    }
+}
+
+impl<'a, A, T, C, P> Iterator for OptimalAssignments<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    type Item = Assignment<'a, A, T, C, P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let strategy = &mut self.strategy;
+        loop {
+            if strategy.proven {
+                if let Some(assignment) = strategy.pending.pop() {
+                    return Some(assignment);
+                }
+            }
+            if self.search.progress().status().is_some() {
+                return None;
+            }
+            // The best assignments are proven once no open node can beat them
+            if let Some(best_score) = &strategy.best_score {
+                strategy.proven = !self
+                    .search
+                    .peek_bound()
+                    .is_some_and(|bound| can_exceed(bound, best_score));
+            }
+            self.search.step(strategy);
+            match self.search.progress().status() {
+                Some(SolveStatus::Optimal) => strategy.proven = true,
+                Some(_) if !strategy.proven => strategy.pending.clear(),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'a, A, T, C, P> Strategy<'a, A, T, C, P> for Optimal<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    fn tighten(&self, node: &mut Node<'a, A, T, C, P>, linear: bool) {
+        node.tighten(self.spec, linear);
+    }
+    fn verdict(&self, bound: &[f64]) -> Verdict {
+        match &self.best_score {
+            Some(best_score) if is_dominated(bound, best_score) => Verdict::End,
+            _ => Verdict::Expand,
+        }
+    }
+    /// Keep the given finished assignment if it is a new or tied best assignment.
+    /// Once the best score is proven, no assignment can beat it anymore.
+    fn handle(&mut self, assignment: Assignment<'a, A, T, C, P>, _: &Progress) -> Control {
+        let score = score(&assignment, self.spec);
+        let ordering = match &self.best_score {
+            None => Some(Ordering::Greater),
+            Some(best_score) => score.partial_cmp(best_score),
+        };
+        match ordering {
+            Some(Ordering::Equal) => {
+                info!("Found maximum assignment -- {}", assignment);
+                self.pending.push(assignment);
+            }
+            Some(Ordering::Greater) if !self.proven => {
+                info!("Found new maximum assignment -- {}", assignment);
+                self.best_score = Some(score);
+                self.pending = vec![assignment];
+            }
+            _ => {}
+        }
+        Control::Continue
+    }
+}
@@ -0,0 +1,56 @@
+use crate::cancel::CancellationToken;
+use std::time::Duration;
+
+/// Options controlling how far the solver is allowed to search.
+///
+/// By default, no limits are set and the solver searches until it has
+/// proven the set of maximum assignments.
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    time_limit: Option<Duration>,
+    node_limit: Option<usize>,
+    open_set_limit: Option<usize>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl SolveOptions {
+    /// Initialize a new set of options without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop searching once the given wall-clock time has passed.
+    pub fn set_time_limit(&mut self, limit: Duration) {
+        self.time_limit = Some(limit);
+    }
+    /// Stop searching once the given number of nodes has been expanded.
+    pub fn set_node_limit(&mut self, limit: usize) {
+        self.node_limit = Some(limit);
+    }
+    /// Stop searching once the set of assignments still to be explored
+    /// grows beyond the given size.
+    pub fn set_open_set_limit(&mut self, limit: usize) {
+        self.open_set_limit = Some(limit);
+    }
+    /// Stop searching once the given token is cancelled.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token);
+    }
+
+    /// Get the wall-clock time limit, if any.
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit
+    }
+    /// Get the limit on the number of expanded nodes, if any.
+    pub fn node_limit(&self) -> Option<usize> {
+        self.node_limit
+    }
+    /// Get the limit on the size of the open set, if any.
+    pub fn open_set_limit(&self) -> Option<usize> {
+        self.open_set_limit
+    }
+    /// Get the cancellation token, if any.
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+}
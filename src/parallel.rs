@@ -0,0 +1,154 @@
+use crate::assignment::Assignment;
+use crate::control::Control;
+use crate::error::GapResult;
+use crate::options::SolveOptions;
+use crate::solution::{Solution, SolveStatus};
+use crate::solver::{
+    collect_solution, process, solve_special_case, Maximum, Progress, Search, Skipped,
+};
+use crate::spec::GapSpec;
+use num::{Num, ToPrimitive};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Solve the assignment problem specified in the given spec,
+/// expanding nodes on the given number of threads at once.
+///
+/// The threads share the open set and the best assignments found so far,
+/// so every thread prunes with the best known score. Each assignment is
+/// generated only once, so the threads never duplicate each other's work.
+/// The result is the same set of maximum assignments as for `solve_with_options`,
+/// and the limits in the given options are respected. Threads finish the node
+/// they are working on when a limit is reached, so the node limit may be exceeded
+/// by up to the number of threads. Returns an error if the spec is inconsistent.
+pub fn solve_parallel<'a, A, T, C, P>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+    threads: usize,
+) -> GapResult<Solution<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug + Send + Sync,
+    T: Hash + Ord + Copy + Debug + Send + Sync,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Send + Sync,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug + Send + Sync,
+{
+    spec.validate()?;
+    let mut progress = Progress::new(options);
+    let mut strategy: Maximum<A, T, C, P, Callback<A, T, C, P>> =
+        Maximum::new(spec, |_, _, _| Control::Continue);
+    if solve_special_case(spec, &mut progress, &mut strategy) {
+        return Ok(collect_solution(
+            strategy.into_assignments(),
+            spec,
+            None,
+            &progress,
+        ));
+    }
+    let mut search = Search::new(spec, progress, &strategy);
+    search.start_greedy(&mut strategy);
+
+    let shared = Shared {
+        state: Mutex::new(State {
+            search,
+            strategy,
+            busy: 0,
+        }),
+        changed: Condvar::new(),
+    };
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| shared.work(spec));
+        }
+    });
+
+    let State {
+        search, strategy, ..
+    } = shared.state.into_inner().unwrap();
+    Ok(collect_solution(
+        strategy.into_assignments(),
+        spec,
+        search.open_bound(),
+        search.progress(),
+    ))
+}
+
+/// The callback of the parallel search, which reports nothing.
+type Callback<'a, A, T, C, P> = fn(&Assignment<'a, A, T, C, P>, Duration, usize) -> Control;
+
+/// The search state shared between threads.
+struct State<'a, A, T, C, P> {
+    search: Search<'a, A, T, C, P>,
+    strategy: Maximum<'a, A, T, C, P, Callback<'a, A, T, C, P>>,
+    /// The number of threads currently working on a node.
+    busy: usize,
+}
+
+/// The shared search state, along with a signal for threads waiting for work.
+struct Shared<'a, A, T, C, P> {
+    state: Mutex<State<'a, A, T, C, P>>,
+    changed: Condvar,
+}
+
+impl<'a, A, T, C, P> Shared<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    /// Keep expanding nodes until the search is finished or cut off.
+    ///
+    /// A thread that runs out of promising nodes waits while other threads are
+    /// busy, as these may still add nodes. The search is finished once no
+    /// thread is busy and no promising node is left.
+    fn work(&self, spec: &'a GapSpec<A, T, C, P>) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let State {
+                search,
+                strategy,
+                busy,
+            } = &mut *state;
+            let node = match search.take(strategy) {
+                Ok(node) => node,
+                Err(Skipped::Pruned) => continue,
+                Err(Skipped::Exhausted) if *busy > 0 => {
+                    state = self.changed.wait(state).unwrap();
+                    continue;
+                }
+                Err(Skipped::Exhausted) => {
+                    search.end(SolveStatus::Optimal);
+                    self.changed.notify_all();
+                    return;
+                }
+                Err(Skipped::Ended) => {
+                    self.changed.notify_all();
+                    return;
+                }
+            };
+            state.busy += 1;
+            drop(state);
+
+            // Tightened nodes are always requeued, as other threads may have
+            // added more promising nodes in the meantime
+            let processed = process(
+                node,
+                spec,
+                |node, linear| node.tighten(spec, linear),
+                |_| true,
+            );
+
+            state = self.state.lock().unwrap();
+            state.busy -= 1;
+            let State {
+                search, strategy, ..
+            } = &mut *state;
+            search.put(processed, strategy);
+            self.changed.notify_all();
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::assignment::Assignment;
+use crate::bound::{gain, is_pareto_dominated, relative_gap, upper_bound_by};
+use crate::control::Control;
+use crate::error::{GapError, GapResult};
+use crate::options::SolveOptions;
+use crate::solution::{Solution, SolveStatus};
+use crate::solver::{Node, Progress, Search, Strategy, Verdict};
+use crate::spec::GapSpec;
+use log::info;
+use num::{Num, ToPrimitive};
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+
+/// Find the Pareto front of the assignment problem specified in the given spec:
+/// every finished assignment whose profit vector is not dominated by that of another.
+///
+/// The profit vector of an assignment sums the profit vectors of its pairs, and every
+/// dimension is maximized or minimized according to the sense of the spec.
+/// One vector dominates another if it is at least as good in every dimension,
+/// and better in at least one. Assignments with the same profit vector are all kept.
+///
+/// The search prunes nodes whose bound in every dimension is dominated by an
+/// assignment on the front. If it is cut off by one of the limits in the given options,
+/// the front found so far is returned. The bound and gap of the solution refer to the
+/// first dimension. Returns an error if the spec is inconsistent, if some pair
+/// has no profit vector, or if the vectors differ in length.
+pub fn solve_pareto<'a, A, T, C, P>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+) -> GapResult<Solution<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    spec.validate()?;
+    let dimensions = dimensions(spec)?;
+    let mut strategy = FrontStrategy {
+        spec,
+        dimensions,
+        front: Vec::new(),
+    };
+    let mut search = Search::new(spec, Progress::new(options), &strategy);
+    search.run(&mut strategy);
+    Ok(collect_front(
+        strategy.front,
+        spec,
+        search.open_bound(),
+        search.progress(),
+    ))
+}
+
+/// The assignments on the Pareto front found so far, along with their gain in every dimension.
+type Front<'a, A, T, C, P> = Vec<(Vec<f64>, Assignment<'a, A, T, C, P>)>;
+
+/// The strategy of searching for the Pareto front, which prunes nodes whose
+/// bound is dominated by an assignment on the front.
+struct FrontStrategy<'a, A, T, C, P> {
+    spec: &'a GapSpec<A, T, C, P>,
+    dimensions: usize,
+    front: Front<'a, A, T, C, P>,
+}
+
+impl<'a, A, T, C, P> Strategy<'a, A, T, C, P> for FrontStrategy<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    fn tighten(&self, node: &mut Node<'a, A, T, C, P>, linear: bool) {
+        node.bound = vector_bounds(
+            &node.assignment,
+            self.spec,
+            node.last,
+            self.dimensions,
+            linear,
+        );
+    }
+    fn verdict(&self, bound: &[f64]) -> Verdict {
+        if self
+            .front
+            .iter()
+            .any(|(values, _)| is_pareto_dominated(bound, values))
+        {
+            Verdict::Prune
+        } else {
+            Verdict::Expand
+        }
+    }
+    fn handle(&mut self, assignment: Assignment<'a, A, T, C, P>, _: &Progress) -> Control {
+        let values = vector_values(&assignment, self.spec, self.dimensions);
+        handle_front_assignment(assignment, values, &mut self.front);
+        Control::Continue
+    }
+}
+
+/// Determine the number of dimensions of the profit vectors of the given spec.
+fn dimensions<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> GapResult<usize, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + PartialOrd + Copy,
+    P: Num + PartialOrd + Copy,
+{
+    let mut dimensions = None;
+    for agent in spec.agents() {
+        for task in spec.eligible_tasks(agent) {
+            let length = spec
+                .profit_vector(agent, task)
+                .ok_or(GapError::MissingProfitVector {
+                    agent: *agent,
+                    task: *task,
+                })?
+                .len();
+            if length == 0 || dimensions.is_some_and(|d| d != length) {
+                return Err(GapError::InconsistentProfitVectors);
+            }
+            dimensions = Some(length);
+        }
+    }
+    Ok(dimensions.unwrap_or(1))
+}
+
+/// Compute the gain of the given assignment in every dimension.
+fn vector_values<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    dimensions: usize,
+) -> Vec<f64>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let mut totals = vec![P::zero(); dimensions];
+    for (agent, tasks) in assignment.assigned() {
+        for task in tasks {
+            let profits = spec.profit_vector(agent, task).unwrap_or_default();
+            for (total, profit) in totals.iter_mut().zip(profits) {
+                *total += *profit;
+            }
+        }
+    }
+    totals.into_iter().map(|total| gain(spec, total)).collect()
+}
+
+/// Bound the gain that any completion of the given assignment can reach in every
+/// dimension, adding only pairs that follow the last added pair.
+/// The linear relaxation is only used if `linear` is set.
+fn vector_bounds<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    last: Option<(A, T)>,
+    dimensions: usize,
+    linear: bool,
+) -> Vec<f64>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    let values = vector_values(assignment, spec, dimensions);
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, current)| {
+            upper_bound_by(
+                assignment,
+                spec,
+                last,
+                current,
+                &mut None,
+                linear,
+                |agent, task| {
+                    spec.profit_vector(agent, task)
+                        .map_or(0.0, |profits| gain(spec, profits[i]))
+                },
+            )
+        })
+        .collect()
+}
+
+/// Add the given finished assignment to the front, unless another assignment on the
+/// front dominates it. Assignments on the front that it dominates are removed.
+fn handle_front_assignment<'a, A, T, C, P>(
+    assignment: Assignment<'a, A, T, C, P>,
+    values: Vec<f64>,
+    front: &mut Front<'a, A, T, C, P>,
+) where
+    A: Debug,
+    T: Debug,
+    P: Debug,
+{
+    if front.iter().any(|(other, _)| dominates(other, &values)) {
+        return;
+    }
+    info!("Found Pareto optimal assignment -- {}", assignment);
+    front.retain(|(other, _)| !dominates(&values, other));
+    front.push((values, assignment));
+}
+
+/// Check whether the first profit vector dominates the second.
+fn dominates(values: &[f64], other: &[f64]) -> bool {
+    values.iter().zip(other).all(|(x, y)| x >= y) && values.iter().zip(other).any(|(x, y)| x > y)
+}
+
+/// Collect the front into a solution. The given bound is the highest bound
+/// on the first dimension of any node that was not yet expanded.
+fn collect_front<'a, A, T, C, P>(
+    front: Front<'a, A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    open_bound: Option<f64>,
+    progress: &Progress,
+) -> Solution<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + PartialOrd + Copy,
+    P: Num + PartialOrd + Copy,
+{
+    let status = match progress.status().unwrap_or(SolveStatus::Optimal) {
+        SolveStatus::Optimal if front.is_empty() => SolveStatus::Infeasible,
+        status => status,
+    };
+    let best = front.iter().map(|(values, _)| values[0]).reduce(f64::max);
+    let bound = match (best, open_bound) {
+        (Some(best), Some(bound)) => best.max(bound),
+        (Some(best), None) => best,
+        (None, Some(bound)) => bound,
+        (None, None) => f64::NEG_INFINITY,
+    };
+    let gap = best.map(|best| relative_gap(bound, best));
+    let assignments: HashSet<Assignment<A, T, C, P>> = front
+        .into_iter()
+        .map(|(_, assignment)| assignment)
+        .collect();
+    Solution::new(
+        assignments,
+        status,
+        progress.nodes_expanded(),
+        progress.start().elapsed(),
+        spec.sense().gain(bound),
+        gap,
+    )
+}
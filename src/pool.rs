@@ -0,0 +1,291 @@
+use crate::assignment::Assignment;
+use crate::bound::is_dominated;
+use crate::control::Control;
+use crate::error::{GapError, GapResult};
+use crate::objective::score;
+use crate::options::SolveOptions;
+use crate::solution::SolveStatus;
+use crate::solver::{Node, Progress, Search, Strategy, Verdict};
+use crate::spec::GapSpec;
+use log::info;
+use num::{Num, ToPrimitive};
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{AddAssign, SubAssign};
+use std::time::Duration;
+
+/// Options controlling which finished assignments are kept in a solution pool.
+///
+/// By default, the pool is unbounded and keeps every finished assignment.
+#[derive(Clone, Debug, Default)]
+pub struct PoolOptions {
+    size: Option<usize>,
+    gap: Option<f64>,
+    min_difference: usize,
+}
+
+impl PoolOptions {
+    /// Initialize a new set of options for an unbounded pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most the given number of assignments, which must be positive.
+    pub fn set_size(&mut self, size: usize) {
+        self.size = Some(size);
+    }
+    /// Only keep assignments whose score on the primary objective is within
+    /// the given gap of the best score, relative to that score.
+    /// For example, a gap of `0.1` keeps assignments within 10% of optimal.
+    pub fn set_gap(&mut self, gap: f64) {
+        self.gap = Some(gap);
+    }
+    /// Require the assignments in the pool to differ in at least the given number
+    /// of agent-task pairs. Among assignments that are too similar, the best is kept.
+    pub fn set_min_difference(&mut self, difference: usize) {
+        self.min_difference = difference;
+    }
+
+    /// Get the maximum number of assignments, if any.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+    /// Get the relative gap to the best score, if any.
+    pub fn gap(&self) -> Option<f64> {
+        self.gap
+    }
+    /// Get the number of agent-task pairs in which the assignments must differ.
+    pub fn min_difference(&self) -> usize {
+        self.min_difference
+    }
+}
+
+/// The result of a solver run that keeps a pool of assignments.
+/// Holds the best assignments found, ranked from best to worst.
+#[derive(Debug)]
+pub struct Pool<'a, A, T, C, P> {
+    assignments: Vec<Assignment<'a, A, T, C, P>>,
+    status: SolveStatus,
+    nodes_expanded: usize,
+    elapsed: Duration,
+}
+
+impl<'a, A, T, C, P> Pool<'a, A, T, C, P> {
+    /// Get the assignments in the pool, from best to worst.
+    pub fn assignments(&self) -> &[Assignment<'a, A, T, C, P>] {
+        &self.assignments
+    }
+    /// Take ownership of the assignments in the pool, from best to worst.
+    pub fn into_assignments(self) -> Vec<Assignment<'a, A, T, C, P>> {
+        self.assignments
+    }
+    /// Get the reason the solver stopped.
+    pub fn status(&self) -> SolveStatus {
+        self.status
+    }
+    /// Check whether the pool is proven to hold the best assignments.
+    pub fn is_optimal(&self) -> bool {
+        self.status == SolveStatus::Optimal
+    }
+    /// Get the number of nodes expanded during the search.
+    pub fn nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+    /// Get the wall-clock time spent searching.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Solve the assignment problem specified in the given spec, keeping a ranked pool
+/// of the best finished assignments rather than only the maximum ones.
+///
+/// Assignments are ranked by the objectives of the spec, and the pool is bounded
+/// by the given pool options. Nodes that cannot beat the worst assignment of a full
+/// pool, or that cannot come within the gap of the best assignment, are pruned.
+/// Without a size or gap, every finished assignment is kept, which requires
+/// searching the entire space.
+///
+/// The minimum difference is applied greedily as assignments are found: an assignment
+/// that is too similar to a better one in the pool is discarded, and replaces
+/// the worse ones it is too similar to. If the search is cut off by one of the limits
+/// in the given options, the pool found so far is returned.
+/// Returns an error if the spec is inconsistent, or if the pool has a size of zero.
+pub fn solve_pool<'a, A, T, C, P>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+    pool_options: &PoolOptions,
+) -> GapResult<Pool<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    spec.validate()?;
+    if pool_options.size == Some(0) {
+        return Err(GapError::EmptyPool);
+    }
+    let mut strategy = PoolStrategy {
+        spec,
+        ranked: Vec::new(),
+        pool_options,
+    };
+    let mut search = Search::new(spec, Progress::new(options), &strategy);
+    search.run(&mut strategy);
+
+    let progress = search.progress();
+    let status = match progress.status().unwrap_or(SolveStatus::Optimal) {
+        SolveStatus::Optimal if strategy.ranked.is_empty() => SolveStatus::Infeasible,
+        status => status,
+    };
+    Ok(Pool {
+        assignments: strategy
+            .ranked
+            .into_iter()
+            .map(|(_, assignment)| assignment)
+            .collect(),
+        status,
+        nodes_expanded: progress.nodes_expanded(),
+        elapsed: progress.start().elapsed(),
+    })
+}
+
+/// The assignments in the pool along with their scores, from best to worst.
+type Ranked<'a, A, T, C, P> = Vec<(Vec<f64>, Assignment<'a, A, T, C, P>)>;
+
+/// The strategy of searching for a pool of assignments, which prunes nodes
+/// that cannot enter the pool.
+struct PoolStrategy<'a, 'o, A, T, C, P> {
+    spec: &'a GapSpec<A, T, C, P>,
+    ranked: Ranked<'a, A, T, C, P>,
+    pool_options: &'o PoolOptions,
+}
+
+impl<'a, 'o, A, T, C, P> Strategy<'a, A, T, C, P> for PoolStrategy<'a, 'o, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    fn tighten(&self, node: &mut Node<'a, A, T, C, P>, linear: bool) {
+        node.tighten(self.spec, linear);
+    }
+    fn verdict(&self, bound: &[f64]) -> Verdict {
+        if cannot_enter(bound, &self.ranked, self.pool_options) {
+            Verdict::End
+        } else {
+            Verdict::Expand
+        }
+    }
+    fn handle(&mut self, assignment: Assignment<'a, A, T, C, P>, _: &Progress) -> Control {
+        let score = score(&assignment, self.spec);
+        handle_pool_assignment(assignment, score, &mut self.ranked, self.pool_options);
+        Control::Continue
+    }
+}
+
+/// Determine whether a node with the given bounds can no longer enter the pool.
+fn cannot_enter<A, T, C, P>(
+    bounds: &[f64],
+    ranked: &Ranked<A, T, C, P>,
+    pool_options: &PoolOptions,
+) -> bool {
+    let full = pool_options.size.is_some_and(|size| ranked.len() >= size);
+    let worst = ranked.last().map(|(score, _)| score);
+    let best = ranked.first().map(|(score, _)| score);
+    (full && worst.is_some_and(|worst| is_dominated(bounds, worst)))
+        || best
+            .zip(pool_options.gap)
+            .is_some_and(|(best, gap)| is_dominated(&bounds[..1], &[threshold(best[0], gap)]))
+}
+
+/// Add the given finished assignment to the pool, if it is good enough
+/// and not too similar to a better assignment in the pool.
+fn handle_pool_assignment<'a, A, T, C, P>(
+    assignment: Assignment<'a, A, T, C, P>,
+    score: Vec<f64>,
+    ranked: &mut Ranked<'a, A, T, C, P>,
+    pool_options: &PoolOptions,
+) where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy + Debug,
+{
+    if ranked.iter().any(|(_, other)| *other == assignment) {
+        return;
+    }
+    if let Some(gap) = pool_options.gap {
+        if ranked
+            .first()
+            .is_some_and(|(best, _)| is_dominated(&score[..1], &[threshold(best[0], gap)]))
+        {
+            return;
+        }
+    }
+    let similar = |other: &Assignment<A, T, C, P>| {
+        difference(&assignment, other) < pool_options.min_difference
+    };
+    if ranked
+        .iter()
+        .any(|(other_score, other)| !is_better(&score, other_score) && similar(other))
+    {
+        return;
+    }
+    ranked.retain(|(_, other)| !similar(other));
+
+    info!("Found pool assignment -- {}", assignment);
+    let position = ranked
+        .iter()
+        .position(|(other_score, _)| is_better(&score, other_score))
+        .unwrap_or(ranked.len());
+    ranked.insert(position, (score, assignment));
+    if let Some(size) = pool_options.size {
+        ranked.truncate(size);
+    }
+    if let Some((best, _)) = ranked.first() {
+        if let Some(gap) = pool_options.gap {
+            let threshold = threshold(best[0], gap);
+            ranked.retain(|(score, _)| !is_dominated(&score[..1], &[threshold]));
+        }
+    }
+}
+
+/// Check whether the first score is strictly better than the second.
+fn is_better(score: &[f64], other: &[f64]) -> bool {
+    score.partial_cmp(other) == Some(Ordering::Greater)
+}
+
+/// Compute the lowest score within the given relative gap of the best score.
+fn threshold(best: f64, gap: f64) -> f64 {
+    best - gap * best.abs()
+}
+
+/// Count the agent-task pairs that are in one assignment, but not in the other.
+fn difference<A, T, C, P>(
+    assignment: &Assignment<A, T, C, P>,
+    other: &Assignment<A, T, C, P>,
+) -> usize
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    let pairs = |x: &Assignment<A, T, C, P>, y: &Assignment<A, T, C, P>| {
+        x.assigned()
+            .iter()
+            .map(|(agent, tasks)| {
+                let others = y.assigned().get(agent);
+                tasks
+                    .iter()
+                    .filter(|task| !others.is_some_and(|ts| ts.contains(task)))
+                    .count()
+            })
+            .sum::<usize>()
+    };
+    pairs(assignment, other) + pairs(other, assignment)
+}
@@ -0,0 +1,210 @@
+/// Tolerance used when testing reduced costs and pivot elements.
+const EPSILON: f64 = 1e-9;
+
+/// A linear program of the form: maximize `c x` subject to `A x <= b` and `0 <= x <= u`,
+/// where `b` is non-negative. The all-zero solution is therefore always feasible.
+#[derive(Debug)]
+pub(crate) struct LinearProgram {
+    rows: usize,
+    cols: usize,
+    a: Vec<f64>,
+    b: Vec<f64>,
+    c: Vec<f64>,
+    u: Vec<f64>,
+}
+
+impl LinearProgram {
+    /// Initialize a linear program with the given constraint bounds and no variables.
+    pub(crate) fn new(b: Vec<f64>) -> Self {
+        Self {
+            rows: b.len(),
+            cols: 0,
+            a: Vec::new(),
+            b,
+            c: Vec::new(),
+            u: Vec::new(),
+        }
+    }
+
+    /// Add a variable with the given objective coefficient and upper bound.
+    /// The column holds the coefficients of the variable in every constraint.
+    pub(crate) fn add_variable(&mut self, c: f64, u: f64, column: &[(usize, f64)]) {
+        // Coefficients are stored column by column
+        let offset = self.a.len();
+        self.a.resize(offset + self.rows, 0.0);
+        for &(row, coefficient) in column {
+            self.a[offset + row] += coefficient;
+        }
+        self.c.push(c);
+        self.u.push(u);
+        self.cols += 1;
+    }
+
+    /// Get the coefficient of the given variable in the given constraint.
+    fn coefficient(&self, row: usize, col: usize) -> f64 {
+        self.a[col * self.rows + row]
+    }
+
+    /// Compute an upper bound on the optimal objective value,
+    /// along with the dual value of every constraint it was derived from.
+    ///
+    /// The problem is solved with the bounded-variable primal simplex method.
+    /// The bound is derived from the dual values of the final tableau, so it
+    /// remains valid even if rounding errors crept into the tableau.
+    pub(crate) fn upper_bound(&self) -> (f64, Vec<f64>) {
+        let duals = self.solve_duals();
+        (self.dual_bound(&duals), duals)
+    }
+
+    /// Run the simplex method and return the dual value of every constraint.
+    fn solve_duals(&self) -> Vec<f64> {
+        let (m, n) = (self.rows, self.cols);
+        let width = n + m;
+
+        // Tableau holding [A | I], with the slack variables as initial basis
+        let mut tableau = vec![0.0; m * width];
+        for i in 0..m {
+            for j in 0..n {
+                tableau[i * width + j] = self.coefficient(i, j);
+            }
+            tableau[i * width + n + i] = 1.0;
+        }
+        let mut reduced: Vec<f64> = self.c.iter().copied().chain(vec![0.0; m]).collect();
+        let upper: Vec<f64> = self
+            .u
+            .iter()
+            .copied()
+            .chain(vec![f64::INFINITY; m])
+            .collect();
+        let mut basis: Vec<usize> = (n..width).collect();
+        let mut values = self.b.clone();
+        let mut is_basic = vec![false; width];
+        for &j in &basis {
+            is_basic[j] = true;
+        }
+        let mut at_upper = vec![false; width];
+
+        // Fall back to Bland's rule after many iterations to prevent cycling
+        let max_iterations = 50 * (width + 1);
+        for iteration in 0..2 * max_iterations {
+            let bland = iteration >= max_iterations;
+
+            // Select the entering variable
+            let mut entering = None;
+            let mut best = EPSILON;
+            for j in 0..width {
+                if is_basic[j] {
+                    continue;
+                }
+                let gain = if at_upper[j] { -reduced[j] } else { reduced[j] };
+                if gain > best {
+                    entering = Some(j);
+                    if bland {
+                        break;
+                    }
+                    best = gain;
+                }
+            }
+            let j = match entering {
+                Some(j) => j,
+                None => break,
+            };
+            let direction = if at_upper[j] { -1.0 } else { 1.0 };
+
+            // Determine how far the entering variable can move
+            let mut step = upper[j];
+            let mut leaving: Option<(usize, bool)> = None;
+            for i in 0..m {
+                let alpha = tableau[i * width + j] * direction;
+                let (limit, leaves_at_upper) = if alpha > EPSILON {
+                    (values[i] / alpha, false)
+                } else if alpha < -EPSILON && upper[basis[i]].is_finite() {
+                    ((upper[basis[i]] - values[i]) / -alpha, true)
+                } else {
+                    continue;
+                };
+                let limit = limit.max(0.0);
+                // Break ties by the lowest variable index
+                let tied = match leaving {
+                    Some((r, _)) => limit <= step + EPSILON && basis[i] < basis[r],
+                    None => false,
+                };
+                if limit < step - EPSILON || tied {
+                    step = limit;
+                    leaving = Some((i, leaves_at_upper));
+                }
+            }
+            if step.is_infinite() {
+                // Cannot happen for bounded variables, barring rounding errors
+                break;
+            }
+
+            // Update the values of the basic variables
+            for i in 0..m {
+                values[i] -= step * direction * tableau[i * width + j];
+            }
+
+            match leaving {
+                None => {
+                    // The entering variable moves to its opposite bound
+                    at_upper[j] = !at_upper[j];
+                }
+                Some((r, leaves_at_upper)) => {
+                    let start = if at_upper[j] { upper[j] } else { 0.0 };
+                    let leaving = basis[r];
+                    is_basic[leaving] = false;
+                    at_upper[leaving] = leaves_at_upper;
+                    is_basic[j] = true;
+                    at_upper[j] = false;
+                    basis[r] = j;
+                    values[r] = start + direction * step;
+                    pivot(&mut tableau, &mut reduced, width, r, j);
+                }
+            }
+        }
+
+        // The reduced cost of a slack variable is minus the dual value of its constraint
+        (0..m).map(|i| (-reduced[n + i]).max(0.0)).collect()
+    }
+
+    /// Compute the Lagrangian bound for the given non-negative dual values.
+    /// By weak duality, this bounds the optimal objective value from above.
+    fn dual_bound(&self, duals: &[f64]) -> f64 {
+        let mut bound: f64 = duals.iter().zip(&self.b).map(|(y, b)| y * b).sum();
+        for j in 0..self.cols {
+            let reduced: f64 = self.c[j]
+                - (0..self.rows)
+                    .map(|i| duals[i] * self.coefficient(i, j))
+                    .sum::<f64>();
+            if reduced > 0.0 {
+                bound += reduced * self.u[j];
+            }
+        }
+        bound
+    }
+}
+
+/// Pivot the tableau on the given row and column.
+fn pivot(tableau: &mut [f64], reduced: &mut [f64], width: usize, row: usize, col: usize) {
+    let factor = tableau[row * width + col];
+    for k in 0..width {
+        tableau[row * width + k] /= factor;
+    }
+    let rows = tableau.len() / width;
+    for i in 0..rows {
+        if i == row {
+            continue;
+        }
+        let factor = tableau[i * width + col];
+        if factor == 0.0 {
+            continue;
+        }
+        for k in 0..width {
+            tableau[i * width + k] -= factor * tableau[row * width + k];
+        }
+    }
+    let factor = reduced[col];
+    for k in 0..width {
+        reduced[k] -= factor * tableau[row * width + k];
+    }
+}
@@ -0,0 +1,97 @@
+use crate::assignment::Assignment;
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// Describes why the solver stopped searching.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SolveStatus {
+    /// The search space was exhausted; the assignments are proven optimal.
+    Optimal,
+    /// The time limit was reached before the search finished.
+    TimeLimit,
+    /// The node limit was reached before the search finished.
+    NodeLimit,
+    /// The open set grew beyond its limit before the search finished.
+    OpenSetLimit,
+    /// The callback asked the solver to stop before the search finished.
+    Stopped,
+    /// The cancellation token was cancelled before the search finished.
+    Cancelled,
+    /// No finished assignment satisfies the constraints of the spec.
+    Infeasible,
+}
+
+/// The result of a solver run.
+/// Holds the best assignments found, and whether they are proven optimal.
+#[derive(Debug)]
+pub struct Solution<'a, A, T, C, P> {
+    assignments: HashSet<Assignment<'a, A, T, C, P>>,
+    status: SolveStatus,
+    nodes_expanded: usize,
+    elapsed: Duration,
+    upper_bound: f64,
+    gap: Option<f64>,
+}
+
+impl<'a, A, T, C, P> Solution<'a, A, T, C, P> {
+    pub(crate) fn new(
+        assignments: HashSet<Assignment<'a, A, T, C, P>>,
+        status: SolveStatus,
+        nodes_expanded: usize,
+        elapsed: Duration,
+        upper_bound: f64,
+        gap: Option<f64>,
+    ) -> Self {
+        Self {
+            assignments,
+            status,
+            nodes_expanded,
+            elapsed,
+            upper_bound,
+            gap,
+        }
+    }
+
+    /// Get the best assignments found.
+    pub fn assignments(&self) -> &HashSet<Assignment<'a, A, T, C, P>> {
+        &self.assignments
+    }
+    /// Take ownership of the best assignments found.
+    pub fn into_assignments(self) -> HashSet<Assignment<'a, A, T, C, P>> {
+        self.assignments
+    }
+    /// Get the reason the solver stopped.
+    pub fn status(&self) -> SolveStatus {
+        self.status
+    }
+    /// Check whether the assignments are proven to be maximal.
+    pub fn is_optimal(&self) -> bool {
+        self.status == SolveStatus::Optimal
+    }
+    /// Get the number of nodes expanded during the search.
+    pub fn nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+    /// Get the wall-clock time spent searching.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+    /// Get a bound on the best profit of any assignment: an upper bound
+    /// when maximizing, and a lower bound when minimizing.
+    /// If the search was cut off, this is derived from the linear relaxation
+    /// of the assignments that were not yet explored.
+    /// If another objective comes first, this bounds the value of that objective
+    /// instead, and so does the gap.
+    pub fn upper_bound(&self) -> f64 {
+        self.upper_bound
+    }
+    /// Get the optimality gap: the difference between the bound and the
+    /// profit of the best assignments found, relative to that profit.
+    /// Profits smaller than one in absolute value count as one, so the gap is
+    /// the absolute difference for an assignment without profit.
+    /// The gap is zero if the assignments are proven optimal,
+    /// and `None` if no assignment was found.
+    pub fn gap(&self) -> Option<f64> {
+        self.gap
+    }
+}
@@ -1,84 +1,631 @@
 use crate::assignment::Assignment;
+use crate::bound::{can_complete, is_dominated, relative_gap, Duals};
+use crate::control::Control;
+use crate::error::{GapError, GapResult};
+use crate::flow;
+use crate::hungarian;
+use crate::knapsack;
+use crate::objective::{bounds, primary_value, score};
+use crate::options::SolveOptions;
+use crate::solution::{Solution, SolveStatus};
 use crate::spec::GapSpec;
 use log::{debug, info, trace};
-use num::Num;
+use num::{Num, ToPrimitive};
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BinaryHeap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::ops::{AddAssign, SubAssign};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Solve the assignment problem specified in the given spec
-pub fn solve<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> HashSet<Assignment<A, T, C, P>>
+/// Solve the assignment problem specified in the given spec.
+/// Returns an error if the spec is inconsistent,
+/// or if no finished assignment satisfies its constraints.
+pub fn solve<A, T, C, P>(
+    spec: &GapSpec<A, T, C, P>,
+) -> GapResult<Assignments<'_, A, T, C, P>, A, T, C>
 where
     A: Hash + Ord + Copy + Debug,
     T: Hash + Ord + Copy + Debug,
-    C: Num + SubAssign + PartialOrd + Copy,
-    P: Num + AddAssign + PartialOrd + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
 {
-    let mut open_set: HashSet<Assignment<A, T, C, P>> = init_open_set(spec);
-    let mut closed_set: HashSet<Assignment<A, T, C, P>> = HashSet::new();
-    let mut finished_set: HashSet<Assignment<A, T, C, P>> = HashSet::new();
-    let mut max_profit = open_set.iter().next().unwrap().profit();
-
-    while !open_set.is_empty() {
-        trace!(
-            "Set sizes -- open: {} - closed: {} - maximum: {}",
-            open_set.len(),
-            closed_set.len(),
-            finished_set.len(),
-        );
-        // Determine the most promising node
-        let current = open_set
-            .iter()
-            .max_by(|x, y| x.profit().partial_cmp(&y.profit()).unwrap())
-            .unwrap()
-            .clone();
-        closed_set.insert(open_set.take(&current).unwrap());
-        trace!("Expanding -- {}", current);
-
-        // Determine all possible next assignments
-        let result = expand_node(&current, spec, &closed_set);
-
-        // Update sets
-        match result {
-            Ok(new_nodes) => open_set.extend(new_nodes),
-            Err(_) => {
-                debug!("Found finished assignment -- {}", current);
-                handle_finished_assignment(&current, &mut max_profit, &mut finished_set)
-            }
+    let solution = solve_with_options(spec, &SolveOptions::new())?;
+    match solution.status() {
+        SolveStatus::Infeasible => Err(GapError::Infeasible),
+        _ => Ok(solution.into_assignments()),
+    }
+}
+
+/// Solve the assignment problem specified in the given spec,
+/// stopping early when one of the limits in the given options is reached,
+/// or when its cancellation token is cancelled.
+/// In that case, the best assignments found so far are returned.
+/// Returns an error if the spec is inconsistent.
+pub fn solve_with_options<'a, A, T, C, P>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+) -> GapResult<Solution<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    solve_with_callback(spec, options, |_, _, _| Control::Continue)
+}
+
+/// Solve the assignment problem specified in the given spec,
+/// calling the given callback whenever a new or tied maximum assignment is found.
+///
+/// The callback receives the assignment, the time elapsed and the number of nodes
+/// expanded so far. If it returns `Control::Stop`, the search stops and the best
+/// assignments found so far are returned. Problems solved without searching report
+/// their maximum assignments as they are enumerated, and are proven optimal
+/// unless the enumeration was cut short.
+/// Returns an error if the spec is inconsistent.
+pub fn solve_with_callback<'a, A, T, C, P, F>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+    callback: F,
+) -> GapResult<Solution<'a, A, T, C, P>, A, T, C>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    F: FnMut(&Assignment<'a, A, T, C, P>, Duration, usize) -> Control,
+{
+    spec.validate()?;
+    Ok(search(spec, options, callback))
+}
+
+/// The maximum assignments found by a solver.
+pub(crate) type Assignments<'a, A, T, C, P> = HashSet<Assignment<'a, A, T, C, P>>;
+
+/// Search the assignment problem specified in the given spec, which must be consistent.
+fn search<'a, A, T, C, P, F>(
+    spec: &'a GapSpec<A, T, C, P>,
+    options: &SolveOptions,
+    callback: F,
+) -> Solution<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    F: FnMut(&Assignment<'a, A, T, C, P>, Duration, usize) -> Control,
+{
+    let mut progress = Progress::new(options);
+    let mut strategy = Maximum::new(spec, callback);
+    if solve_special_case(spec, &mut progress, &mut strategy) {
+        return collect_solution(strategy.into_assignments(), spec, None, &progress);
+    }
+    let mut search = Search::new(spec, progress, &strategy);
+    search.run(&mut strategy);
+    collect_solution(
+        strategy.into_assignments(),
+        spec,
+        search.open_bound(),
+        search.progress(),
+    )
+}
+
+/// Solve the given spec without searching, if it has a structure that allows this,
+/// handing every assignment to the given strategy as soon as it is enumerated.
+/// Returns whether the spec was solved this way.
+///
+/// Every step of enumerating the maximum assignments counts as an expanded node.
+/// The enumeration stops once a limit is reached or the strategy asks to stop,
+/// in which case the progress holds the reason it was cut off.
+pub(crate) fn solve_special_case<'a, A, T, C, P, S>(
+    spec: &'a GapSpec<A, T, C, P>,
+    progress: &mut Progress,
+    strategy: &mut S,
+) -> bool
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    S: Strategy<'a, A, T, C, P>,
+{
+    let mut report = |assignment, progress: &Progress| strategy.handle(assignment, progress);
+    let control = if hungarian::applies(spec) {
+        debug!("Solving as a linear assignment problem");
+        hungarian::solve(spec, progress, &mut report)
+    } else if flow::applies(spec) {
+        debug!("Solving as a minimum cost flow problem");
+        flow::solve(spec, progress, &mut report)
+    } else if knapsack::applies(spec) {
+        debug!("Solving as a knapsack problem for every agent");
+        match knapsack::solve(spec, progress, &mut report) {
+            Ok(control) => control,
+            Err(_) => return false,
         }
+    } else {
+        return false;
+    };
+    if control == Control::Stop {
+        progress.end(SolveStatus::Stopped);
     }
-    finished_set
+    true
 }
 
-/// Initialize set of assignments to explore
-fn init_open_set<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> HashSet<Assignment<A, T, C, P>>
+/// Collect the results of the search into a solution.
+/// The given bound is the highest bound on the primary score of any node
+/// that was not yet expanded. The search was finished unless the progress
+/// holds the reason it was cut off.
+pub(crate) fn collect_solution<'a, A, T, C, P>(
+    finished_set: HashSet<Assignment<'a, A, T, C, P>>,
+    spec: &GapSpec<A, T, C, P>,
+    open_bound: Option<f64>,
+    progress: &Progress,
+) -> Solution<'a, A, T, C, P>
 where
     A: Hash + Ord + Copy,
     T: Hash + Ord + Copy,
-    C: Num + SubAssign + PartialOrd + Copy,
-    P: Num + AddAssign + PartialOrd + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
 {
-    let mut open_set = HashSet::new();
-    let start = Assignment::from_spec(spec);
-    open_set.insert(start);
-    open_set
+    // Without constraints beyond the budgets, some assignment is always finished
+    let status = match progress.status().unwrap_or(SolveStatus::Optimal) {
+        SolveStatus::Optimal if finished_set.is_empty() => SolveStatus::Infeasible,
+        status => status,
+    };
+    let best = finished_set
+        .iter()
+        .next()
+        .map(|assignment| score(assignment, spec)[0]);
+    let bound = match (best, open_bound) {
+        (Some(best), Some(bound)) => best.max(bound),
+        (Some(best), None) => best,
+        (None, Some(bound)) => bound,
+        (None, None) => f64::NEG_INFINITY,
+    };
+    let gap = best.map(|best| relative_gap(bound, best));
+    Solution::new(
+        finished_set,
+        status,
+        progress.nodes_expanded(),
+        progress.start().elapsed(),
+        primary_value(spec, bound),
+        gap,
+    )
+}
+
+/// Determine whether any of the search limits has been reached
+fn check_limits(
+    options: &SolveOptions,
+    start: Instant,
+    nodes_expanded: usize,
+    open_set_size: usize,
+) -> Option<SolveStatus> {
+    if options
+        .cancellation_token()
+        .is_some_and(|token| token.is_cancelled())
+    {
+        return Some(SolveStatus::Cancelled);
+    }
+    if let Some(limit) = options.time_limit() {
+        if start.elapsed() >= limit {
+            return Some(SolveStatus::TimeLimit);
+        }
+    }
+    if let Some(limit) = options.node_limit() {
+        if nodes_expanded >= limit {
+            return Some(SolveStatus::NodeLimit);
+        }
+    }
+    if let Some(limit) = options.open_set_limit() {
+        if open_set_size > limit {
+            return Some(SolveStatus::OpenSetLimit);
+        }
+    }
+    None
+}
+
+/// The progress of a solver run: when it started, how many nodes it expanded,
+/// and why it ended, if it did.
+pub(crate) struct Progress {
+    options: SolveOptions,
+    start: Instant,
+    nodes_expanded: usize,
+    status: Option<SolveStatus>,
 }
 
-/// Determine all possible new tasks for an agent for the given assignment
+impl Progress {
+    /// Start tracking a solver run with the given options.
+    pub(crate) fn new(options: &SolveOptions) -> Self {
+        Self {
+            options: options.clone(),
+            start: Instant::now(),
+            nodes_expanded: 0,
+            status: None,
+        }
+    }
+
+    /// Determine whether any of the limits has been reached, given the number of
+    /// nodes held in memory. Once a limit is reached, it stays reached.
+    pub(crate) fn limit_reached(&mut self, held: usize) -> bool {
+        if self.status.is_none() {
+            if let Some(status) = check_limits(&self.options, self.start, self.nodes_expanded, held)
+            {
+                self.end(status);
+            }
+        }
+        self.status.is_some()
+    }
+    /// End the run for the given reason, unless it already ended.
+    pub(crate) fn end(&mut self, status: SolveStatus) {
+        if self.status.is_none() {
+            if status != SolveStatus::Optimal {
+                info!("Stopping search early -- {:?}", status);
+            }
+            self.status = Some(status);
+        }
+    }
+    /// Count one more expanded node.
+    pub(crate) fn count_node(&mut self) {
+        self.nodes_expanded += 1;
+    }
+
+    /// Get the time the run started.
+    pub(crate) fn start(&self) -> Instant {
+        self.start
+    }
+    /// Get the number of nodes expanded so far.
+    pub(crate) fn nodes_expanded(&self) -> usize {
+        self.nodes_expanded
+    }
+    /// Get the reason the run ended, if it did.
+    pub(crate) fn status(&self) -> Option<SolveStatus> {
+        self.status
+    }
+}
+
+/// What a best-first search keeps of the finished assignments it finds,
+/// and how that limits the nodes still worth expanding.
+pub(crate) trait Strategy<'a, A, T, C, P> {
+    /// Tighten the bound of the given node on the scores that any completion of its
+    /// assignment can reach. Bounds on the profit only solve its linear relaxation
+    /// if `linear` is set.
+    fn tighten(&self, node: &mut Node<'a, A, T, C, P>, linear: bool);
+    /// Decide what to do with the most promising node left, given its bound.
+    fn verdict(&self, bound: &[f64]) -> Verdict;
+    /// Handle a finished assignment that satisfies the constraints of the spec.
+    /// Returns whether the search should go on.
+    fn handle(&mut self, assignment: Assignment<'a, A, T, C, P>, progress: &Progress) -> Control;
+}
+
+/// What to do with the most promising node left in a search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    /// The node may still improve the result.
+    Expand,
+    /// The node cannot improve the result, but less promising nodes still might.
+    Prune,
+    /// No node left can improve the result, so the search is finished.
+    End,
+}
+
+/// The reason no node was taken from the open set.
+pub(crate) enum Skipped {
+    /// The most promising node cannot improve the result, so it was discarded.
+    Pruned,
+    /// No node left can improve the result.
+    Exhausted,
+    /// The search ended, or a limit was reached.
+    Ended,
+}
+
+/// The result of processing a node taken from the open set.
+pub(crate) enum Processed<'a, A, T, C, P> {
+    /// The assignment of the node cannot be completed, so the node was discarded.
+    Pruned,
+    /// The bound of the node was tightened, and it is no longer the most promising.
+    Requeued(Node<'a, A, T, C, P>),
+    /// The node was expanded into the given nodes.
+    Expanded(Vec<Node<'a, A, T, C, P>>),
+    /// The assignment of the node cannot be expanded any further.
+    Finished(Assignment<'a, A, T, C, P>),
+}
+
+/// A best-first search over the assignments of a spec. The open set holds the nodes
+/// left to explore, which are expanded in order of their bound. What is kept of the
+/// finished assignments, and which nodes are pruned, is up to a strategy.
+pub(crate) struct Search<'a, A, T, C, P> {
+    spec: &'a GapSpec<A, T, C, P>,
+    progress: Progress,
+    open_set: BinaryHeap<Node<'a, A, T, C, P>>,
+}
+
+impl<'a, A, T, C, P> Search<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    /// Start a search of the given spec from the empty assignment.
+    pub(crate) fn new<S>(spec: &'a GapSpec<A, T, C, P>, progress: Progress, strategy: &S) -> Self
+    where
+        S: Strategy<'a, A, T, C, P>,
+    {
+        let mut root = Node {
+            bound: Vec::new(),
+            tightness: Tightness::Linear,
+            duals: None,
+            last: None,
+            assignment: Assignment::from_spec(spec),
+        };
+        strategy.tighten(&mut root, true);
+        let mut open_set = BinaryHeap::new();
+        open_set.push(root);
+        Self {
+            spec,
+            progress,
+            open_set,
+        }
+    }
+
+    /// Search until the search is finished, or until it is cut off.
+    pub(crate) fn run<S: Strategy<'a, A, T, C, P>>(&mut self, strategy: &mut S) {
+        self.start_greedy(strategy);
+        while self.progress.status().is_none() {
+            self.step(strategy);
+        }
+    }
+    /// Start with a greedy assignment, so a result is available if the search is cut off.
+    pub(crate) fn start_greedy<S: Strategy<'a, A, T, C, P>>(&mut self, strategy: &mut S) {
+        let greedy = match self.open_set.peek() {
+            Some(root) => greedy_assignment(root, self.spec),
+            None => return,
+        };
+        debug!("Found greedy assignment -- {}", greedy);
+        if !is_feasible(&greedy, self.spec) {
+            return;
+        }
+        if strategy.handle(greedy, &self.progress) == Control::Stop {
+            self.progress.end(SolveStatus::Stopped);
+        }
+    }
+    /// Take the most promising node, and expand it or tighten its bound.
+    pub(crate) fn step<S: Strategy<'a, A, T, C, P>>(&mut self, strategy: &mut S) {
+        let node = match self.take(strategy) {
+            Ok(node) => node,
+            Err(Skipped::Exhausted) => return self.progress.end(SolveStatus::Optimal),
+            Err(Skipped::Pruned | Skipped::Ended) => return,
+        };
+        let open_set = &self.open_set;
+        let processed = process(
+            node,
+            self.spec,
+            |node, linear| strategy.tighten(node, linear),
+            // Unless the tightened bound rules the node out,
+            // it can be expanded at once if it is still the most promising
+            |bound| {
+                open_set.peek().is_some_and(|next| bound < &next.bound[..])
+                    || strategy.verdict(bound) != Verdict::Expand
+            },
+        );
+        self.put(processed, strategy);
+    }
+
+    /// Take the most promising node from the open set, unless no node
+    /// can improve the result, or the search ended.
+    pub(crate) fn take<S: Strategy<'a, A, T, C, P>>(
+        &mut self,
+        strategy: &S,
+    ) -> Result<Node<'a, A, T, C, P>, Skipped> {
+        if self.progress.status().is_some() {
+            return Err(Skipped::Ended);
+        }
+        let bound = match self.open_set.peek() {
+            Some(node) => &node.bound,
+            None => return Err(Skipped::Exhausted),
+        };
+        trace!("Set sizes -- open: {}", self.open_set.len());
+        let verdict = strategy.verdict(bound);
+        if self.progress.limit_reached(self.open_set.len()) {
+            return Err(Skipped::Ended);
+        }
+        match verdict {
+            Verdict::Expand => Ok(self.open_set.pop().unwrap()),
+            Verdict::Prune => {
+                let node = self.open_set.pop().unwrap();
+                trace!("Cannot improve the result -- {}", node.assignment);
+                Err(Skipped::Pruned)
+            }
+            // Nodes are expanded in order of their bound, so once the most
+            // promising node cannot improve the result, no other node can either
+            Verdict::End => {
+                debug!("Remaining nodes cannot improve the result");
+                Err(Skipped::Exhausted)
+            }
+        }
+    }
+    /// Put the result of processing a node back into the search.
+    pub(crate) fn put<S: Strategy<'a, A, T, C, P>>(
+        &mut self,
+        processed: Processed<'a, A, T, C, P>,
+        strategy: &mut S,
+    ) {
+        match processed {
+            Processed::Pruned => {}
+            Processed::Requeued(node) => self.open_set.push(node),
+            Processed::Expanded(new_nodes) => {
+                self.progress.count_node();
+                self.open_set.extend(new_nodes);
+            }
+            Processed::Finished(assignment) => {
+                self.progress.count_node();
+                self.finish(assignment, strategy);
+            }
+        }
+    }
+    /// Hand the given finished assignment to the strategy,
+    /// if it satisfies the constraints of the spec.
+    fn finish<S: Strategy<'a, A, T, C, P>>(
+        &mut self,
+        assignment: Assignment<'a, A, T, C, P>,
+        strategy: &mut S,
+    ) {
+        if !is_feasible(&assignment, self.spec) {
+            debug!("Found incomplete assignment -- {}", assignment);
+            return;
+        }
+        debug!("Found finished assignment -- {}", assignment);
+        if strategy.handle(assignment, &self.progress) == Control::Stop {
+            self.progress.end(SolveStatus::Stopped);
+        }
+    }
+}
+
+impl<'a, A, T, C, P> Search<'a, A, T, C, P> {
+    /// End the search for the given reason, unless it already ended.
+    pub(crate) fn end(&mut self, status: SolveStatus) {
+        self.progress.end(status);
+    }
+    /// Get the progress of the search.
+    pub(crate) fn progress(&self) -> &Progress {
+        &self.progress
+    }
+    /// Get the bound of the most promising node left, if any.
+    pub(crate) fn peek_bound(&self) -> Option<&[f64]> {
+        self.open_set.peek().map(|node| &node.bound[..])
+    }
+    /// Get the highest bound on the primary score of any node left,
+    /// unless the search is finished.
+    pub(crate) fn open_bound(&self) -> Option<f64> {
+        match self.progress.status() {
+            None | Some(SolveStatus::Optimal) => None,
+            Some(_) => self.peek_bound().map(|bound| bound[0]),
+        }
+    }
+}
+
+/// Process the given node, taken from the open set of a search: prune it if it
+/// cannot be completed, tighten its bound, or expand it. After each tightening,
+/// the node is requeued if the given function says so.
+pub(crate) fn process<'a, A, T, C, P>(
+    mut node: Node<'a, A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+    tighten: impl Fn(&mut Node<'a, A, T, C, P>, bool),
+    requeue: impl Fn(&[f64]) -> bool,
+) -> Processed<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+{
+    if spec.has_side_constraints() && !can_complete(&node.assignment, spec, node.last) {
+        trace!("Cannot be completed -- {}", node.assignment);
+        return Processed::Pruned;
+    }
+    // Nodes inherit the bound of their parent; tighten it before expanding.
+    // Most nodes are no longer the most promising once their linear relaxation
+    // is bounded with the dual values of their parent, so it is only solved for the others.
+    while node.tightness != Tightness::Linear {
+        let linear = node.tightness == Tightness::Dual;
+        tighten(&mut node, linear);
+        node.tightness = if linear {
+            Tightness::Linear
+        } else {
+            Tightness::Dual
+        };
+        if requeue(&node.bound) {
+            return Processed::Requeued(node);
+        }
+    }
+    trace!("Expanding -- {}", node.assignment);
+    match expand_node(&node, spec) {
+        Some(new_nodes) => Processed::Expanded(new_nodes),
+        None => Processed::Finished(node.assignment),
+    }
+}
+
+/// The strategy of searching for the maximum assignments, which reports
+/// every new or tied maximum assignment to a callback.
+pub(crate) struct Maximum<'a, A, T, C, P, F> {
+    spec: &'a GapSpec<A, T, C, P>,
+    finished_set: HashSet<Assignment<'a, A, T, C, P>>,
+    best_score: Vec<f64>,
+    callback: F,
+}
+
+impl<'a, A, T, C, P, F> Maximum<'a, A, T, C, P, F> {
+    /// Search the given spec for its maximum assignments.
+    pub(crate) fn new(spec: &'a GapSpec<A, T, C, P>, callback: F) -> Self {
+        Self {
+            spec,
+            finished_set: HashSet::new(),
+            best_score: Vec::new(),
+            callback,
+        }
+    }
+    /// Take the maximum assignments found so far.
+    pub(crate) fn into_assignments(self) -> HashSet<Assignment<'a, A, T, C, P>> {
+        self.finished_set
+    }
+}
+
+impl<'a, A, T, C, P, F> Strategy<'a, A, T, C, P> for Maximum<'a, A, T, C, P, F>
+where
+    A: Hash + Ord + Copy + Debug,
+    T: Hash + Ord + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    F: FnMut(&Assignment<'a, A, T, C, P>, Duration, usize) -> Control,
+{
+    fn tighten(&self, node: &mut Node<'a, A, T, C, P>, linear: bool) {
+        node.tighten(self.spec, linear);
+    }
+    fn verdict(&self, bound: &[f64]) -> Verdict {
+        if !self.finished_set.is_empty() && is_dominated(bound, &self.best_score) {
+            Verdict::End
+        } else {
+            Verdict::Expand
+        }
+    }
+    fn handle(&mut self, assignment: Assignment<'a, A, T, C, P>, progress: &Progress) -> Control {
+        if handle_finished_assignment(
+            &assignment,
+            self.spec,
+            &mut self.best_score,
+            &mut self.finished_set,
+        ) {
+            (self.callback)(
+                &assignment,
+                progress.start().elapsed(),
+                progress.nodes_expanded(),
+            )
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+/// Determine all possible new tasks for an agent for the given assignment.
+///
+/// To generate every assignment only once, agent-task pairs are added in
+/// increasing order: only pairs following the last assigned pair are expanded.
+/// The assignment is finished if no pair at all can be added.
 fn expand_node<'a, A, T, C, P>(
-    assignment: &Assignment<'a, A, T, C, P>,
+    node: &Node<'a, A, T, C, P>,
     spec: &GapSpec<A, T, C, P>,
-    closed_set: &HashSet<Assignment<A, T, C, P>>,
-) -> Result<HashSet<Assignment<'a, A, T, C, P>>, &'a str>
+) -> Option<Vec<Node<'a, A, T, C, P>>>
 where
     A: Hash + Ord + Copy,
     T: Hash + Ord + Copy,
     C: Num + SubAssign + PartialOrd + Copy,
     P: Num + AddAssign + PartialOrd + Copy,
 {
-    let mut new_nodes = HashSet::new();
+    let assignment = &node.assignment;
+    let mut new_nodes = Vec::new();
+    let mut finished = true;
 
     for agent in spec.agents() {
         // Determine agent budget
@@ -86,11 +633,10 @@ where
         if agent_budget == C::zero() {
             continue;
         };
-        // Determine all possible tasks for the agent
+        // Determine all possible tasks for the agent, among its eligible tasks
         let assigned = assignment.agent_tasks(agent);
         let possible_tasks = spec
-            .tasks()
-            .iter()
+            .eligible_tasks(agent)
             .copied()
             // Agent cannot be assigned to the same task twice
             .filter(|t| match assigned {
@@ -100,46 +646,180 @@ where
             // Tasks within agent budget
             .filter(|t| spec.agent_cost(agent, t) <= agent_budget)
             // Tasks with enough budget for agent
-            .filter(|t| spec.task_cost(agent, t) <= assignment.task_budget(t));
+            .filter(|t| spec.task_cost(agent, t) <= assignment.task_budget(t))
+            // Pairs within agent and task resources, without conflicting tasks
+            .filter(|t| assignment.fits_constraints(agent, t));
 
         // Create assignments for each task
         for t in possible_tasks {
-            let mut next = assignment.clone();
-            next.assign(agent, &t).unwrap();
-            if !closed_set.contains(&next) {
-                new_nodes.insert(next);
+            finished = false;
+            if !follows(node.last, (*agent, t)) {
+                continue;
             }
+            let mut next = assignment.clone();
+            next.assign_fitting(agent, &t);
+            new_nodes.push(Node {
+                bound: node.bound.clone(),
+                tightness: Tightness::Inherited,
+                duals: node.duals.clone(),
+                last: Some((*agent, t)),
+                assignment: next,
+            });
         }
     }
-    if new_nodes.is_empty() {
-        Err("Assignment is finished and cannot be expanded.")
+    if finished {
+        None
     } else {
-        Ok(new_nodes)
+        Some(new_nodes)
+    }
+}
+
+/// Complete the assignment of the given node by repeatedly adding the best pair.
+fn greedy_assignment<'a, A, T, C, P>(
+    node: &Node<'a, A, T, C, P>,
+    spec: &GapSpec<A, T, C, P>,
+) -> Assignment<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    let mut current = Node {
+        bound: node.bound.clone(),
+        tightness: Tightness::Linear,
+        duals: None,
+        last: None,
+        assignment: node.assignment.clone(),
+    };
+    while let Some(new_nodes) = expand_node(&current, spec) {
+        current = new_nodes
+            .into_iter()
+            .max_by(|x, y| {
+                spec.sense()
+                    .compare(&x.assignment.profit(), &y.assignment.profit())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap();
+        current.last = None;
+    }
+    current.assignment
+}
+
+/// Check whether the given finished assignment satisfies the constraints of the spec
+/// beyond the budgets.
+fn is_feasible<A, T, C, P>(assignment: &Assignment<A, T, C, P>, spec: &GapSpec<A, T, C, P>) -> bool
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy,
+    P: Num + AddAssign + PartialOrd + Copy,
+{
+    (!spec.complete() || assignment.is_complete())
+        && assignment.meets_minimums()
+        && assignment.meets_pairings()
+}
+
+/// Check whether the given agent-task pair may be added after the last added pair.
+pub(crate) fn follows<A: Ord, T: Ord>(last: Option<(A, T)>, pair: (A, T)) -> bool {
+    match last {
+        None => true,
+        Some(last) => pair > last,
     }
 }
 
-/// Manage set of finished assignments.
+/// Manage set of finished assignments, given the scores of the best assignments so far.
+/// Returns whether the assignment is a new or tied best assignment.
 fn handle_finished_assignment<'a, A, T, C, P>(
     assignment: &Assignment<'a, A, T, C, P>,
-    max_profit: &mut P,
+    spec: &GapSpec<A, T, C, P>,
+    best_score: &mut Vec<f64>,
     finished_set: &mut HashSet<Assignment<'a, A, T, C, P>>,
-) where
+) -> bool
+where
     A: Hash + Ord + Copy + Debug,
     T: Hash + Ord + Copy + Debug,
-    C: Num + SubAssign + PartialOrd + Copy,
-    P: Num + AddAssign + PartialOrd + Copy + Debug,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
 {
-    match assignment.profit().partial_cmp(max_profit) {
+    // The first finished assignment is always the best so far
+    let score = score(assignment, spec);
+    let ordering = if finished_set.is_empty() {
+        Some(Ordering::Greater)
+    } else {
+        score.partial_cmp(best_score)
+    };
+    match ordering {
         Some(Ordering::Equal) => {
             info!("Found maximum assignment -- {}", assignment);
-            finished_set.insert(assignment.clone());
+            finished_set.insert(assignment.clone())
         }
         Some(Ordering::Greater) => {
             info!("Found new maximum assignment -- {}", assignment);
-            *max_profit = assignment.profit();
+            *best_score = score;
             *finished_set = HashSet::new();
-            finished_set.insert(assignment.clone());
+            finished_set.insert(assignment.clone())
         }
-        _ => {}
+        _ => false,
+    }
+}
+
+/// An assignment waiting to be expanded, along with upper bounds on its scores.
+/// Nodes are ordered by their bounds, so the most promising node is expanded first.
+/// New nodes inherit the bound of their parent, which is only tightened
+/// once the node reaches the front of the queue. The dual values of the linear
+/// relaxation of the parent are kept to bound that of the node cheaply.
+pub(crate) struct Node<'a, A, T, C, P> {
+    pub(crate) bound: Vec<f64>,
+    pub(crate) tightness: Tightness,
+    pub(crate) duals: Option<Arc<Duals<A, T>>>,
+    pub(crate) last: Option<(A, T)>,
+    pub(crate) assignment: Assignment<'a, A, T, C, P>,
+}
+
+impl<'a, A, T, C, P> Node<'a, A, T, C, P>
+where
+    A: Hash + Ord + Copy,
+    T: Hash + Ord + Copy,
+    C: Num + SubAssign + PartialOrd + Copy + ToPrimitive,
+    P: Num + AddAssign + PartialOrd + Copy + ToPrimitive,
+{
+    /// Tighten the bound of the node on the scores of the objectives of the given spec.
+    pub(crate) fn tighten(&mut self, spec: &GapSpec<A, T, C, P>, linear: bool) {
+        self.bound = bounds(&self.assignment, spec, self.last, &mut self.duals, linear);
+    }
+}
+
+/// How tight the bound of a node is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Tightness {
+    /// The bound was inherited from the parent of the node.
+    Inherited,
+    /// The bound on the profit bounds its linear relaxation with the dual values
+    /// of the parent of the node, without solving it.
+    Dual,
+    /// The bound on the profit solves its linear relaxation.
+    Linear,
+}
+
+impl<'a, A, T, C, P> Ord for Node<'a, A, T, C, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound
+            .iter()
+            .zip(&other.bound)
+            .map(|(x, y)| x.total_cmp(y))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+impl<'a, A, T, C, P> PartialOrd for Node<'a, A, T, C, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<'a, A, T, C, P> PartialEq for Node<'a, A, T, C, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
     }
 }
+impl<'a, A, T, C, P> Eq for Node<'a, A, T, C, P> {}
@@ -1,9 +1,56 @@
+use crate::error::{GapError, GapResult};
+use crate::objective::Objective;
 use num::Num;
+use std::cmp::Ordering;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
+/// Whether the solver looks for the assignments with the highest or lowest profit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Sense {
+    /// Find the assignments with the highest profit.
+    #[default]
+    Maximize,
+    /// Find the assignments with the lowest profit, e.g. when profits are costs.
+    Minimize,
+}
+
+impl Sense {
+    /// Convert a profit into a gain, which is higher for better assignments.
+    /// Converting a gain again yields the original profit.
+    pub(crate) fn gain(self, profit: f64) -> f64 {
+        match self {
+            Sense::Maximize => profit,
+            Sense::Minimize => -profit,
+        }
+    }
+    /// Compare two profits, where the better profit is greater.
+    pub(crate) fn compare<P: PartialOrd>(self, profit: &P, other: &P) -> Option<Ordering> {
+        match self {
+            Sense::Maximize => profit.partial_cmp(other),
+            Sense::Minimize => other.partial_cmp(profit),
+        }
+    }
+}
+
+/// Whether the solver prefers assignments in which the worst-off agent does well.
+/// This is a shorthand for the most common lists of objectives.
+///
+/// The fairness of an assignment is the profit of its worst-off agent:
+/// the lowest agent profit when maximizing, and the highest when minimizing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Fairness {
+    /// Return all assignments with the best profit.
+    #[default]
+    Ignore,
+    /// Among the assignments with the best profit, return the fairest ones.
+    TieBreak,
+    /// Return the fairest assignments, breaking ties by profit.
+    Primary,
+}
+
 /// Define the assignment problem configuration
 #[derive(Debug)]
 pub struct GapSpec<A, T, C, P> {
@@ -11,10 +58,27 @@ pub struct GapSpec<A, T, C, P> {
     tasks: HashSet<T>,
     agent_budgets: HashMap<A, C>,
     task_budgets: HashMap<T, C>,
+    eligible: HashMap<A, Vec<T>>,
     agent_cost: HashMap<(A, T), C>,
     task_cost: HashMap<(A, T), C>,
+    agent_resources: HashMap<A, Vec<C>>,
+    task_resources: HashMap<T, Vec<C>>,
+    agent_resource_costs: HashMap<(A, T), Vec<C>>,
+    task_resource_costs: HashMap<(A, T), Vec<C>>,
     profit: HashMap<(A, T), P>,
     assigned: HashMap<A, HashSet<T>>,
+    forbidden: HashSet<(A, T)>,
+    required: HashSet<(A, T)>,
+    conflicts: HashMap<T, BTreeSet<T>>,
+    incompatible: HashMap<A, BTreeSet<A>>,
+    paired: HashMap<A, BTreeSet<A>>,
+    sense: Sense,
+    complete: bool,
+    agent_tolerances: HashMap<A, C>,
+    agent_minimums: HashMap<A, C>,
+    task_minimums: HashMap<T, C>,
+    objectives: Vec<Objective<A, T, C, P>>,
+    profit_vectors: HashMap<(A, T), Vec<P>>,
 }
 
 impl<A, T, C, P> GapSpec<A, T, C, P>
@@ -24,11 +88,30 @@ where
     C: Num + PartialOrd + Copy,
     P: Num + PartialOrd + Copy,
 {
-    /// Initialize a new assignment problem specification.
+    /// Initialize a new assignment problem specification,
+    /// in which every agent may be assigned to every task.
     pub fn new<M, N>(agents: M, tasks: N) -> Self
     where
         M: IntoIterator<Item = A>,
         N: IntoIterator<Item = T>,
+    {
+        let agents: Vec<A> = agents.into_iter().collect();
+        let tasks: Vec<T> = tasks.into_iter().collect();
+        let pairs: Vec<(A, T)> = agents
+            .iter()
+            .flat_map(|a| tasks.iter().map(move |t| (*a, *t)))
+            .collect();
+        Self::new_sparse(agents, tasks, pairs)
+    }
+
+    /// Initialize a new assignment problem specification, in which agents may only
+    /// be assigned to the tasks they are paired with. Costs and profits only need
+    /// to be set for the given pairs; all other pairs are not allowed.
+    pub fn new_sparse<M, N, E>(agents: M, tasks: N, pairs: E) -> Self
+    where
+        M: IntoIterator<Item = A>,
+        N: IntoIterator<Item = T>,
+        E: IntoIterator<Item = (A, T)>,
     {
         let agents: HashSet<A> = agents.into_iter().collect();
         let tasks: HashSet<T> = tasks.into_iter().collect();
@@ -42,15 +125,19 @@ where
         for t in &tasks {
             task_budgets.insert(*t, C::one());
         }
+        let mut eligible: HashMap<A, Vec<T>> = HashMap::new();
         let mut agent_cost = HashMap::new();
         let mut task_cost = HashMap::new();
         let mut profit = HashMap::new();
-        for a in &agents {
-            for t in &tasks {
-                agent_cost.insert((*a, *t), C::one());
-                task_cost.insert((*a, *t), C::one());
-                profit.insert((*a, *t), P::one());
-            }
+        for (a, t) in pairs {
+            eligible.entry(a).or_default().push(t);
+            agent_cost.insert((a, t), C::one());
+            task_cost.insert((a, t), C::one());
+            profit.insert((a, t), P::one());
+        }
+        for tasks in eligible.values_mut() {
+            tasks.sort();
+            tasks.dedup();
         }
 
         Self {
@@ -59,21 +146,35 @@ where
             agent_budgets,
             agent_cost,
             task_budgets,
+            eligible,
             task_cost,
+            agent_resources: HashMap::new(),
+            task_resources: HashMap::new(),
+            agent_resource_costs: HashMap::new(),
+            task_resource_costs: HashMap::new(),
             profit,
             assigned: HashMap::new(),
+            forbidden: HashSet::new(),
+            required: HashSet::new(),
+            conflicts: HashMap::new(),
+            incompatible: HashMap::new(),
+            paired: HashMap::new(),
+            sense: Sense::Maximize,
+            complete: false,
+            agent_tolerances: HashMap::new(),
+            agent_minimums: HashMap::new(),
+            task_minimums: HashMap::new(),
+            objectives: vec![Objective::Profit],
+            profit_vectors: HashMap::new(),
         }
     }
 
     /// Set the budget for a single agent
-    pub fn set_agent_budget(&mut self, agent: A, budget: C) -> Result<C, String>
-    where
-        A: Debug,
-    {
+    pub fn set_agent_budget(&mut self, agent: A, budget: C) -> GapResult<C, A, T, C> {
         if let Entry::Occupied(mut e) = self.agent_budgets.entry(agent) {
             Ok(e.insert(budget))
         } else {
-            Err(format!("Agent {:?} not present in configuration.", agent))
+            Err(GapError::UnknownAgent(agent))
         }
     }
 
@@ -100,6 +201,38 @@ where
     pub fn set_task_cost(&mut self, cost: HashMap<(A, T), C>) {
         self.task_cost = cost;
     }
+    /// Set all agent resources at once. Every vector holds the amount of every resource
+    /// the agent has, besides its budget. Either all agents or none have resources.
+    pub fn set_agent_resources<M>(&mut self, resources: M)
+    where
+        M: IntoIterator<Item = (A, Vec<C>)>,
+    {
+        self.agent_resources = resources.into_iter().collect();
+    }
+    /// Set all task resources at once. Every vector holds the amount of every resource
+    /// the task has, besides its budget. Either all tasks or none have resources.
+    pub fn set_task_resources<M>(&mut self, resources: M)
+    where
+        M: IntoIterator<Item = (T, Vec<C>)>,
+    {
+        self.task_resources = resources.into_iter().collect();
+    }
+    /// Set all agent resource costs at once. Every vector holds the cost of the pair
+    /// in every resource of the agent; pairs without a vector cost nothing.
+    pub fn set_agent_resource_costs<M>(&mut self, costs: M)
+    where
+        M: IntoIterator<Item = ((A, T), Vec<C>)>,
+    {
+        self.agent_resource_costs = costs.into_iter().collect();
+    }
+    /// Set all task resource costs at once. Every vector holds the cost of the pair
+    /// in every resource of the task; pairs without a vector cost nothing.
+    pub fn set_task_resource_costs<M>(&mut self, costs: M)
+    where
+        M: IntoIterator<Item = ((A, T), Vec<C>)>,
+    {
+        self.task_resource_costs = costs.into_iter().collect();
+    }
 
     /// Set all profits at once.
     pub fn set_profits<M>(&mut self, profit: M)
@@ -108,6 +241,14 @@ where
     {
         self.profit = profit.into_iter().collect();
     }
+    /// Set all profit vectors at once, for finding the Pareto front with `solve_pareto`.
+    /// Every vector holds the profit of the pair in every dimension.
+    pub fn set_profit_vectors<M>(&mut self, profit_vectors: M)
+    where
+        M: IntoIterator<Item = ((A, T), Vec<P>)>,
+    {
+        self.profit_vectors = profit_vectors.into_iter().collect();
+    }
     /// Set all pre-assigned agents at once.
     pub fn set_assigned<M, N>(&mut self, assigned: M)
     where
@@ -119,6 +260,113 @@ where
             .map(|(agent, tasks)| (agent, tasks.into_iter().collect()))
             .collect();
     }
+    /// Set all pairs that may never be assigned at once.
+    pub fn set_forbidden<M>(&mut self, forbidden: M)
+    where
+        M: IntoIterator<Item = (A, T)>,
+    {
+        self.forbidden = forbidden.into_iter().collect();
+    }
+    /// Set all pairs that must be part of every assignment at once.
+    /// Like pre-assigned pairs, they are assigned before solving.
+    pub fn set_required<M>(&mut self, required: M)
+    where
+        M: IntoIterator<Item = (A, T)>,
+    {
+        self.required = required.into_iter().collect();
+    }
+    /// Set all groups of conflicting tasks at once, for example of tasks that overlap
+    /// in time. An agent may be assigned to at most one task of every group.
+    pub fn set_conflicts<M, N>(&mut self, groups: M)
+    where
+        M: IntoIterator<Item = N>,
+        N: IntoIterator<Item = T>,
+    {
+        self.conflicts = HashMap::new();
+        for group in groups {
+            let group: BTreeSet<T> = group.into_iter().collect();
+            if group.len() < 2 {
+                continue;
+            }
+            for task in &group {
+                self.conflicts
+                    .entry(*task)
+                    .or_default()
+                    .extend(group.iter().filter(|t| *t != task));
+            }
+        }
+    }
+    /// Set all pairs of agents that may never share a task at once.
+    pub fn set_incompatible<M>(&mut self, incompatible: M)
+    where
+        M: IntoIterator<Item = (A, A)>,
+    {
+        self.incompatible = symmetric(incompatible);
+    }
+    /// Set all pairs of agents that must share every task at once: whenever one agent
+    /// of a pair is assigned to a task, so is the other. Like minimums, pairings are
+    /// checked on finished assignments.
+    pub fn set_paired<M>(&mut self, paired: M)
+    where
+        M: IntoIterator<Item = (A, A)>,
+    {
+        self.paired = symmetric(paired);
+    }
+    /// Set whether to maximize or minimize the profit.
+    pub fn set_sense(&mut self, sense: Sense) {
+        self.sense = sense;
+    }
+    /// Require every agent to spend its entire budget, up to its tolerance.
+    pub fn set_complete(&mut self, complete: bool) {
+        self.complete = complete;
+    }
+    /// Set the budget that agents may leave unspent in a complete assignment.
+    /// Agents without a tolerance must spend their entire budget.
+    pub fn set_agent_tolerances<M>(&mut self, tolerances: M)
+    where
+        M: IntoIterator<Item = (A, C)>,
+    {
+        self.agent_tolerances = tolerances.into_iter().collect();
+    }
+    /// Set the minimum budget that agents must spend in a finished assignment.
+    /// Agents without a minimum may spend nothing.
+    pub fn set_agent_minimums<M>(&mut self, minimums: M)
+    where
+        M: IntoIterator<Item = (A, C)>,
+    {
+        self.agent_minimums = minimums.into_iter().collect();
+    }
+    /// Set the minimum budget that tasks must spend in a finished assignment,
+    /// for example the number of agents covering the task when task costs equal one.
+    /// Tasks without a minimum may spend nothing.
+    pub fn set_task_minimums<M>(&mut self, minimums: M)
+    where
+        M: IntoIterator<Item = (T, C)>,
+    {
+        self.task_minimums = minimums.into_iter().collect();
+    }
+    /// Set how the profit of the worst-off agent is taken into account,
+    /// replacing the objectives of the spec.
+    pub fn set_fairness(&mut self, fairness: Fairness) {
+        self.objectives = match fairness {
+            Fairness::Ignore => vec![Objective::Profit],
+            Fairness::TieBreak => vec![Objective::Profit, Objective::Fairness],
+            Fairness::Primary => vec![Objective::Fairness, Objective::Profit],
+        };
+    }
+    /// Set the objectives by which assignments are ranked, from most to least important.
+    /// Returns an error if no objective is given.
+    pub fn set_objectives<M>(&mut self, objectives: M) -> GapResult<(), A, T, C>
+    where
+        M: IntoIterator<Item = Objective<A, T, C, P>>,
+    {
+        let objectives: Vec<Objective<A, T, C, P>> = objectives.into_iter().collect();
+        if objectives.is_empty() {
+            return Err(GapError::NoObjectives);
+        }
+        self.objectives = objectives;
+        Ok(())
+    }
 
     /// Get the list of all agents.
     pub fn agents(&self) -> &HashSet<A> {
@@ -129,6 +377,27 @@ where
         &self.tasks
     }
 
+    /// Get the tasks the given agent may be assigned to, in increasing order.
+    /// Forbidden pairs are left out.
+    pub fn eligible_tasks<'s>(&'s self, agent: &'s A) -> impl Iterator<Item = &'s T> + 's {
+        self.eligible
+            .get(agent)
+            .into_iter()
+            .flatten()
+            .filter(move |task| !self.is_forbidden(agent, task))
+    }
+    /// Check whether the given agent may be assigned to the given task.
+    pub fn is_eligible(&self, agent: &A, task: &T) -> bool {
+        self.eligible
+            .get(agent)
+            .is_some_and(|tasks| tasks.binary_search(task).is_ok())
+            && !self.is_forbidden(agent, task)
+    }
+    /// Check whether the given pair may never be assigned.
+    pub fn is_forbidden(&self, agent: &A, task: &T) -> bool {
+        !self.forbidden.is_empty() && self.forbidden.contains(&(*agent, *task))
+    }
+
     /// Get the agent cost associated with the given agent-task combination.
     /// If the agent is assigned to the task, this cost will be deducted
     /// from its budget.
@@ -141,6 +410,20 @@ where
     pub fn task_cost(&self, agent: &A, task: &T) -> C {
         self.task_cost[&(*agent, *task)]
     }
+    /// Get the cost in every resource of the agent associated with the given
+    /// agent-task combination. The slice is empty if the pair costs nothing.
+    pub fn agent_resource_costs(&self, agent: &A, task: &T) -> &[C] {
+        self.agent_resource_costs
+            .get(&(*agent, *task))
+            .map_or(&[], |costs| costs.as_slice())
+    }
+    /// Get the cost in every resource of the task associated with the given
+    /// agent-task combination. The slice is empty if the pair costs nothing.
+    pub fn task_resource_costs(&self, agent: &A, task: &T) -> &[C] {
+        self.task_resource_costs
+            .get(&(*agent, *task))
+            .map_or(&[], |costs| costs.as_slice())
+    }
     /// Get the profit associated with the given agent-task combination.
     /// If the agent is assigned to the task, this profit will be added
     /// to the total assignment profit.
@@ -148,6 +431,13 @@ where
         self.profit[&(*agent, *task)]
     }
 
+    /// Get the profit vector associated with the given agent-task combination, if any.
+    pub fn profit_vector(&self, agent: &A, task: &T) -> Option<&[P]> {
+        self.profit_vectors
+            .get(&(*agent, *task))
+            .map(|profits| profits.as_slice())
+    }
+
     /// Get the map of agent budgets.
     pub fn agent_budgets(&self) -> &HashMap<A, C> {
         &self.agent_budgets
@@ -156,8 +446,383 @@ where
     pub fn task_budgets(&self) -> &HashMap<T, C> {
         &self.task_budgets
     }
+    /// Get the map of agent resources.
+    pub fn agent_resources(&self) -> &HashMap<A, Vec<C>> {
+        &self.agent_resources
+    }
+    /// Get the map of task resources.
+    pub fn task_resources(&self) -> &HashMap<T, Vec<C>> {
+        &self.task_resources
+    }
     /// Get the map of assigned agent-task combinations.
     pub fn assigned(&self) -> &HashMap<A, HashSet<T>> {
         &self.assigned
     }
+    /// Get the set of pairs that may never be assigned.
+    pub fn forbidden(&self) -> &HashSet<(A, T)> {
+        &self.forbidden
+    }
+    /// Get the set of pairs that must be part of every assignment.
+    pub fn required(&self) -> &HashSet<(A, T)> {
+        &self.required
+    }
+    /// Get the tasks that conflict with the given task, in increasing order.
+    pub fn conflicting_tasks(&self, task: &T) -> impl Iterator<Item = &T> {
+        self.conflicts.get(task).into_iter().flatten()
+    }
+    /// Get the agents that may never share a task with the given agent, in increasing order.
+    pub fn incompatible_agents(&self, agent: &A) -> impl Iterator<Item = &A> {
+        self.incompatible.get(agent).into_iter().flatten()
+    }
+    /// Get the agents that must share every task with the given agent, in increasing order.
+    pub fn paired_agents(&self, agent: &A) -> impl Iterator<Item = &A> {
+        self.paired.get(agent).into_iter().flatten()
+    }
+    /// Get whether the profit is maximized or minimized.
+    pub fn sense(&self) -> Sense {
+        self.sense
+    }
+    /// Check whether agents are required to spend their entire budget.
+    pub fn complete(&self) -> bool {
+        self.complete
+    }
+    /// Get the budget the given agent may leave unspent in a complete assignment.
+    pub fn agent_tolerance(&self, agent: &A) -> C {
+        self.agent_tolerances
+            .get(agent)
+            .copied()
+            .unwrap_or_else(C::zero)
+    }
+    /// Get the minimum budget the given agent must spend in a finished assignment.
+    pub fn agent_minimum(&self, agent: &A) -> C {
+        self.agent_minimums
+            .get(agent)
+            .copied()
+            .unwrap_or_else(C::zero)
+    }
+    /// Get the minimum budget the given task must spend in a finished assignment.
+    pub fn task_minimum(&self, task: &T) -> C {
+        self.task_minimums
+            .get(task)
+            .copied()
+            .unwrap_or_else(C::zero)
+    }
+    /// Get the objectives by which assignments are ranked, from most to least important.
+    pub fn objectives(&self) -> &[Objective<A, T, C, P>] {
+        &self.objectives
+    }
+
+    /// Check the spec for inconsistencies: agents and tasks without a budget,
+    /// eligible pairs without costs or a profit, resource vectors that differ in length,
+    /// entries for agents and tasks that are not part of the spec, and pre-assigned
+    /// or required pairs that are forbidden, not eligible, conflict with each other,
+    /// share a task between incompatible agents, or exceed the budgets or resources.
+    /// Returns every inconsistency found at once, as a `GapError::InvalidSpec`.
+    ///
+    /// The solvers validate the spec before solving, and return this error
+    /// rather than panicking on an inconsistent spec.
+    pub fn validate(&self) -> GapResult<(), A, T, C> {
+        let mut errors = Vec::new();
+        let mut agents: Vec<A> = self.agents.iter().copied().collect();
+        let mut tasks: Vec<T> = self.tasks.iter().copied().collect();
+        agents.sort();
+        tasks.sort();
+
+        // Check budgets
+        for agent in &agents {
+            if !self.agent_budgets.contains_key(agent) {
+                errors.push(GapError::MissingAgentBudget(*agent));
+            }
+        }
+        for task in &tasks {
+            if !self.task_budgets.contains_key(task) {
+                errors.push(GapError::MissingTaskBudget(*task));
+            }
+        }
+        // Check costs and profits
+        for agent in &agents {
+            for task in self.eligible_tasks(agent) {
+                let pair = (*agent, *task);
+                if !self.agent_cost.contains_key(&pair) {
+                    errors.push(GapError::MissingAgentCost {
+                        agent: *agent,
+                        task: *task,
+                    });
+                }
+                if !self.task_cost.contains_key(&pair) {
+                    errors.push(GapError::MissingTaskCost {
+                        agent: *agent,
+                        task: *task,
+                    });
+                }
+                if !self.profit.contains_key(&pair) {
+                    errors.push(GapError::MissingProfit {
+                        agent: *agent,
+                        task: *task,
+                    });
+                }
+            }
+        }
+        // Check resources
+        if !self.resources_consistent() {
+            errors.push(GapError::InconsistentResources);
+        }
+        // Check entries for agents and tasks that are not part of the spec
+        let pairs = self
+            .agent_cost
+            .keys()
+            .chain(self.task_cost.keys())
+            .chain(self.agent_resource_costs.keys())
+            .chain(self.task_resource_costs.keys())
+            .chain(self.profit.keys())
+            .chain(self.profit_vectors.keys())
+            .chain(self.forbidden.iter())
+            .chain(self.required.iter());
+        let unknown_agents: BTreeSet<A> = self
+            .agent_budgets
+            .keys()
+            .chain(self.agent_tolerances.keys())
+            .chain(self.agent_resources.keys())
+            .chain(self.incompatible.keys())
+            .chain(self.paired.keys())
+            .chain(self.agent_minimums.keys())
+            .chain(self.assigned.keys())
+            .chain(pairs.clone().map(|(agent, _)| agent))
+            .filter(|agent| !self.agents.contains(agent))
+            .copied()
+            .collect();
+        let unknown_tasks: BTreeSet<T> = self
+            .task_budgets
+            .keys()
+            .chain(self.task_minimums.keys())
+            .chain(self.task_resources.keys())
+            .chain(self.conflicts.keys())
+            .chain(self.assigned.values().flatten())
+            .chain(pairs.map(|(_, task)| task))
+            .filter(|task| !self.tasks.contains(task))
+            .copied()
+            .collect();
+        errors.extend(unknown_agents.into_iter().map(GapError::UnknownAgent));
+        errors.extend(unknown_tasks.into_iter().map(GapError::UnknownTask));
+
+        // Check pre-assigned and required pairs against the budgets and resources
+        let mut task_spent: BTreeMap<T, C> = BTreeMap::new();
+        let mut task_resources_spent: BTreeMap<T, Vec<C>> = BTreeMap::new();
+        let mut task_agents: BTreeMap<T, Vec<A>> = BTreeMap::new();
+        for agent in &agents {
+            let mut agent_spent = C::zero();
+            let mut agent_resources_spent = Vec::new();
+            let assigned: BTreeSet<T> = self
+                .assigned
+                .get(agent)
+                .into_iter()
+                .flatten()
+                .copied()
+                .chain(
+                    self.required
+                        .iter()
+                        .filter(|(a, _)| a == agent)
+                        .map(|(_, task)| *task),
+                )
+                .collect();
+            for task in &assigned {
+                if let Some(conflict) = self
+                    .conflicting_tasks(task)
+                    .find(|t| *t < task && assigned.contains(t))
+                {
+                    errors.push(GapError::ConflictingTasks {
+                        agent: *agent,
+                        task: *task,
+                        conflict: *conflict,
+                    });
+                }
+                if self.is_forbidden(agent, task) {
+                    errors.push(GapError::ForbiddenPair {
+                        agent: *agent,
+                        task: *task,
+                    });
+                    continue;
+                }
+                if self.tasks.contains(task) && !self.is_eligible(agent, task) {
+                    errors.push(GapError::IneligiblePair {
+                        agent: *agent,
+                        task: *task,
+                    });
+                    continue;
+                }
+                let task_agents = task_agents.entry(*task).or_default();
+                if let Some(other) = task_agents
+                    .iter()
+                    .find(|other| self.incompatible_agents(agent).any(|a| a == *other))
+                {
+                    errors.push(GapError::IncompatibleAgents {
+                        agent: *agent,
+                        task: *task,
+                        other: *other,
+                    });
+                }
+                task_agents.push(*agent);
+                let pair = (*agent, *task);
+                if let Some(cost) = self.agent_cost.get(&pair) {
+                    agent_spent = agent_spent + *cost;
+                }
+                if let Some(cost) = self.task_cost.get(&pair) {
+                    let spent = task_spent.entry(*task).or_insert_with(C::zero);
+                    *spent = *spent + *cost;
+                }
+                add_costs(
+                    &mut agent_resources_spent,
+                    self.agent_resource_costs(agent, task),
+                );
+                add_costs(
+                    task_resources_spent.entry(*task).or_default(),
+                    self.task_resource_costs(agent, task),
+                );
+            }
+            match self.agent_budgets.get(agent) {
+                Some(budget) if agent_spent > *budget => {
+                    errors.push(GapError::AgentBudgetExceeded {
+                        agent: *agent,
+                        needed: agent_spent,
+                        remaining: *budget,
+                    });
+                }
+                _ => {}
+            }
+            if let Some((resource, needed, remaining)) =
+                exceeded(&agent_resources_spent, self.agent_resources.get(agent))
+            {
+                errors.push(GapError::AgentResourceExceeded {
+                    agent: *agent,
+                    resource,
+                    needed,
+                    remaining,
+                });
+            }
+        }
+        for (task, task_spent) in task_spent {
+            match self.task_budgets.get(&task) {
+                Some(budget) if task_spent > *budget => {
+                    errors.push(GapError::TaskBudgetExceeded {
+                        task,
+                        needed: task_spent,
+                        remaining: *budget,
+                    });
+                }
+                _ => {}
+            }
+        }
+        for (task, spent) in task_resources_spent {
+            if let Some((resource, needed, remaining)) =
+                exceeded(&spent, self.task_resources.get(&task))
+            {
+                errors.push(GapError::TaskResourceExceeded {
+                    task,
+                    resource,
+                    needed,
+                    remaining,
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(GapError::InvalidSpec(errors))
+        }
+    }
+
+    /// Check whether all agents and all tasks have the same number of resources,
+    /// and whether every resource cost vector matches that number.
+    fn resources_consistent(&self) -> bool {
+        fn consistent<K, C>(
+            keys: &HashSet<K>,
+            resources: &HashMap<K, Vec<C>>,
+            costs: &HashMap<impl Hash + Eq, Vec<C>>,
+        ) -> bool
+        where
+            K: Hash + Eq,
+        {
+            let count = resources.values().next().map_or(0, Vec::len);
+            (resources.is_empty() || keys.iter().all(|key| resources.contains_key(key)))
+                && resources.values().all(|r| r.len() == count)
+                && costs.values().all(|c| c.len() == count)
+        }
+        consistent(
+            &self.agents,
+            &self.agent_resources,
+            &self.agent_resource_costs,
+        ) && consistent(&self.tasks, &self.task_resources, &self.task_resource_costs)
+    }
+
+    /// Check whether the spec has constraints beyond the budgets,
+    /// which the solvers for special cases do not support.
+    pub(crate) fn has_side_constraints(&self) -> bool {
+        self.complete
+            || !self.agent_minimums.is_empty()
+            || !self.task_minimums.is_empty()
+            || self.has_resources()
+            || self.has_conflicts()
+            || !self.incompatible.is_empty()
+            || !self.paired.is_empty()
+    }
+    /// Check whether any task conflicts with another task.
+    pub(crate) fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+    /// Check whether any agent or task has resources besides its budget.
+    pub(crate) fn has_resources(&self) -> bool {
+        !self.agent_resources.is_empty() || !self.task_resources.is_empty()
+    }
+    /// Check whether the spec ranks assignments by anything other than their profit first,
+    /// which the solvers for special cases do not support.
+    pub(crate) fn has_side_objectives(&self) -> bool {
+        !matches!(self.objectives[0], Objective::Profit)
+    }
+}
+
+/// Collect the given pairs of agents into a map from every agent to the agents
+/// it is paired with, in both directions. Pairs of an agent with itself are left out.
+fn symmetric<A, M>(pairs: M) -> HashMap<A, BTreeSet<A>>
+where
+    A: Hash + Ord + Copy,
+    M: IntoIterator<Item = (A, A)>,
+{
+    let mut map: HashMap<A, BTreeSet<A>> = HashMap::new();
+    for (agent, other) in pairs {
+        if agent != other {
+            map.entry(agent).or_default().insert(other);
+            map.entry(other).or_default().insert(agent);
+        }
+    }
+    map
+}
+
+/// Add the given costs to the given resources spent, one resource at a time.
+fn add_costs<C: Num + Copy>(spent: &mut Vec<C>, costs: &[C]) {
+    if spent.len() < costs.len() {
+        spent.resize(costs.len(), C::zero());
+    }
+    for (spent, cost) in spent.iter_mut().zip(costs) {
+        *spent = *spent + *cost;
+    }
+}
+
+/// Find the first resource in which the given costs exceed the given remaining resources.
+pub(crate) fn first_exceeded<C: PartialOrd>(costs: &[C], remaining: &[C]) -> Option<usize> {
+    costs
+        .iter()
+        .zip(remaining)
+        .position(|(cost, remaining)| cost > remaining)
+}
+
+/// Find the first resource in which the given resources spent exceed the given resources,
+/// along with the amount spent and the amount available.
+fn exceeded<C: PartialOrd + Copy>(
+    spent: &[C],
+    resources: Option<&Vec<C>>,
+) -> Option<(usize, C, C)> {
+    let resources = resources?;
+    first_exceeded(spent, resources)
+        .map(|resource| (resource, spent[resource], resources[resource]))
 }
@@ -1,4 +1,105 @@
-use gap_solver::{solve, Assignment, GapSpec};
+use gap_solver::{
+    solve_knapsack, solve_lagrangian, solve_pareto, Assignment, CancellationToken, Control,
+    Fairness, GapError, GapSpec, Objective, PoolOptions, Sense, SolveOptions, SolveStatus,
+};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use consistent::*;
+
+/// Solvers for specs that are known to be consistent.
+/// They unwrap the result, so that a validation error fails the test.
+/// Tests of inconsistent specs call the solvers of the crate instead.
+mod consistent {
+    use gap_solver::{
+        Assignment, GapSpec, OptimalAssignments, Pool, PoolOptions, Solution, SolveOptions,
+    };
+    use num::{Num, ToPrimitive};
+    use std::collections::HashSet;
+    use std::fmt::Debug;
+    use std::hash::Hash;
+    use std::ops::{AddAssign, SubAssign};
+    use std::time::Duration;
+
+    pub fn solve<A, T, C, P>(spec: &GapSpec<A, T, C, P>) -> HashSet<Assignment<'_, A, T, C, P>>
+    where
+        A: Hash + Ord + Copy + Debug,
+        T: Hash + Ord + Copy + Debug,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    {
+        gap_solver::solve(spec).unwrap()
+    }
+
+    pub fn solve_with_options<'a, A, T, C, P>(
+        spec: &'a GapSpec<A, T, C, P>,
+        options: &SolveOptions,
+    ) -> Solution<'a, A, T, C, P>
+    where
+        A: Hash + Ord + Copy + Debug,
+        T: Hash + Ord + Copy + Debug,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    {
+        gap_solver::solve_with_options(spec, options).unwrap()
+    }
+
+    pub fn solve_with_callback<'a, A, T, C, P, F>(
+        spec: &'a GapSpec<A, T, C, P>,
+        options: &SolveOptions,
+        callback: F,
+    ) -> Solution<'a, A, T, C, P>
+    where
+        A: Hash + Ord + Copy + Debug,
+        T: Hash + Ord + Copy + Debug,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        F: FnMut(&Assignment<'a, A, T, C, P>, Duration, usize) -> gap_solver::Control,
+    {
+        gap_solver::solve_with_callback(spec, options, callback).unwrap()
+    }
+
+    pub fn solve_parallel<'a, A, T, C, P>(
+        spec: &'a GapSpec<A, T, C, P>,
+        options: &SolveOptions,
+        threads: usize,
+    ) -> Solution<'a, A, T, C, P>
+    where
+        A: Hash + Ord + Copy + Debug + Send + Sync,
+        T: Hash + Ord + Copy + Debug + Send + Sync,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug + Send + Sync,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug + Send + Sync,
+    {
+        gap_solver::solve_parallel(spec, options, threads).unwrap()
+    }
+
+    pub fn solve_pool<'a, A, T, C, P>(
+        spec: &'a GapSpec<A, T, C, P>,
+        options: &SolveOptions,
+        pool_options: &PoolOptions,
+    ) -> Pool<'a, A, T, C, P>
+    where
+        A: Hash + Ord + Copy + Debug,
+        T: Hash + Ord + Copy + Debug,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    {
+        gap_solver::solve_pool(spec, options, pool_options).unwrap()
+    }
+
+    pub fn solve_iter<'a, A, T, C, P>(
+        spec: &'a GapSpec<A, T, C, P>,
+        options: &SolveOptions,
+    ) -> OptimalAssignments<'a, A, T, C, P>
+    where
+        A: Hash + Ord + Copy + Debug,
+        T: Hash + Ord + Copy + Debug,
+        C: Num + SubAssign + PartialOrd + Copy + ToPrimitive + Debug,
+        P: Num + AddAssign + PartialOrd + Copy + ToPrimitive + Debug,
+    {
+        gap_solver::solve_iter(spec, options).unwrap()
+    }
+}
 
 #[test]
 fn default_spec() {
@@ -152,3 +253,1570 @@ fn medium_problem_spec() {
     assert!(result.contains(&truth1));
     assert!(result.contains(&truth2));
 }
+
+#[test]
+fn assign_errors() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+    spec.set_agent_budgets([("a", 1), ("b", 2)]);
+    spec.set_task_budgets([("1", 1), ("2", 2)]);
+    assert_eq!(
+        spec.set_agent_budget("c", 1),
+        Err(GapError::UnknownAgent("c"))
+    );
+
+    let mut assignment = Assignment::from_spec(&spec);
+    assert_eq!(assignment.assign(&"a", &"1"), Ok(()));
+    assert_eq!(
+        assignment.assign(&"a", &"1"),
+        Err(GapError::DuplicateAssignment {
+            agent: "a",
+            task: "1"
+        })
+    );
+    assert_eq!(
+        assignment.assign(&"a", &"2"),
+        Err(GapError::AgentBudgetExceeded {
+            agent: "a",
+            needed: 1,
+            remaining: 0
+        })
+    );
+    assert_eq!(
+        assignment.assign(&"b", &"1"),
+        Err(GapError::TaskBudgetExceeded {
+            task: "1",
+            needed: 1,
+            remaining: 0
+        })
+    );
+    assert_eq!(
+        assignment.assign(&"c", &"1"),
+        Err(GapError::UnknownAgent("c"))
+    );
+    assert_eq!(
+        assignment.assign(&"b", &"3"),
+        Err(GapError::UnknownTask("3"))
+    );
+    assert_eq!(assignment.profit(), 1);
+}
+
+#[test]
+fn validate_spec() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+    assert_eq!(spec.validate(), Ok(()));
+
+    spec.set_task_budgets([("1", 1), ("3", 1)]);
+    spec.set_agent_cost(HashMap::from([
+        (("a", "1"), 1),
+        (("a", "2"), 1),
+        (("b", "1"), 1),
+    ]));
+    spec.set_profits([
+        (("a", "1"), 1),
+        (("a", "2"), 1),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+    ]);
+    spec.set_assigned([("a", vec!["1", "2"]), ("c", vec!["1"])]);
+    let errors = vec![
+        GapError::MissingTaskBudget("2"),
+        GapError::MissingAgentCost {
+            agent: "b",
+            task: "2",
+        },
+        GapError::UnknownAgent("c"),
+        GapError::UnknownTask("3"),
+        GapError::AgentBudgetExceeded {
+            agent: "a",
+            needed: 2,
+            remaining: 1,
+        },
+    ];
+    assert_eq!(spec.validate(), Err(GapError::InvalidSpec(errors.clone())));
+
+    // Solvers return the report instead of panicking
+    let options = SolveOptions::new();
+    assert_eq!(
+        gap_solver::solve(&spec),
+        Err(GapError::InvalidSpec(errors.clone()))
+    );
+    assert!(gap_solver::solve_parallel(&spec, &options, 2).is_err());
+    assert!(gap_solver::solve_pool(&spec, &options, &PoolOptions::new()).is_err());
+    assert!(gap_solver::solve_iter(&spec, &options).is_err());
+    assert_eq!(
+        solve_knapsack(&spec).unwrap_err(),
+        GapError::InvalidSpec(errors)
+    );
+}
+
+#[test]
+fn validate_pre_assigned_task_budget() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1"]);
+    spec.set_assigned([("a", vec!["1"]), ("b", vec!["1"])]);
+    let error = GapError::TaskBudgetExceeded {
+        task: "1",
+        needed: 2,
+        remaining: 1,
+    };
+    assert_eq!(
+        gap_solver::solve_with_options(&spec, &SolveOptions::new()).unwrap_err(),
+        GapError::InvalidSpec(vec![error])
+    );
+}
+
+#[test]
+fn sparse_spec() {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2", "3"];
+    let pairs = [("a", "1"), ("a", "2"), ("b", "2"), ("c", "3")];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new_sparse(agents, tasks, pairs);
+    spec.set_profits([
+        (("a", "1"), 1),
+        (("a", "2"), 3),
+        (("b", "2"), 2),
+        (("c", "3"), 1),
+    ]);
+    assert!(spec.eligible_tasks(&"a").eq(&["1", "2"]));
+    assert!(!spec.is_eligible(&"b", &"1"));
+
+    let truth = HashSet::from([
+        Assignment::from_assigned([("a", vec!["2"]), ("c", vec!["3"])], &spec),
+        Assignment::from_assigned(
+            [("a", vec!["1"]), ("b", vec!["2"]), ("c", vec!["3"])],
+            &spec,
+        ),
+    ]);
+    assert_eq!(solve(&spec), truth);
+    let found: HashSet<_> = solve_iter(&spec, &SolveOptions::new()).collect();
+    assert_eq!(found, truth);
+
+    let mut assignment = Assignment::from_spec(&spec);
+    assert_eq!(
+        assignment.assign(&"b", &"1"),
+        Err(GapError::IneligiblePair {
+            agent: "b",
+            task: "1"
+        })
+    );
+    spec.set_assigned([("b", vec!["1"])]);
+    assert_eq!(
+        spec.validate(),
+        Err(GapError::InvalidSpec(vec![GapError::IneligiblePair {
+            agent: "b",
+            task: "1"
+        }]))
+    );
+}
+
+#[test]
+fn forbidden_and_required_pairs() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 1),
+        (("b", "1"), 1),
+        (("b", "2"), 3),
+    ]);
+
+    // Without task 1, agent a can only take task 2, which agent b is better at
+    spec.set_forbidden([("a", "1")]);
+    let result = solve(&spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&Assignment::from_assigned([("b", vec!["2"])], &spec)));
+
+    spec.set_required([("a", "2")]);
+    let result = solve(&spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&Assignment::from_assigned(
+        [("a", vec!["2"]), ("b", vec!["1"])],
+        &spec
+    )));
+}
+
+#[test]
+fn required_pairs_infeasible() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+    spec.set_forbidden([("a", "1")]);
+    spec.set_required([("a", "1"), ("a", "2"), ("b", "2")]);
+    let errors = vec![
+        GapError::ForbiddenPair {
+            agent: "a",
+            task: "1",
+        },
+        GapError::TaskBudgetExceeded {
+            task: "2",
+            needed: 2,
+            remaining: 1,
+        },
+    ];
+    assert_eq!(
+        gap_solver::solve_with_options(&spec, &SolveOptions::new()).unwrap_err(),
+        GapError::InvalidSpec(errors)
+    );
+}
+
+#[test]
+fn conflicting_tasks() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2", "3"]);
+    spec.set_agent_budgets([("a", 3), ("b", 3)]);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 3),
+        (("a", "3"), 3),
+        (("b", "1"), 1),
+        (("b", "2"), 2),
+        (("b", "3"), 1),
+    ]);
+    spec.set_conflicts([vec!["1", "2"]]);
+    assert!(spec.conflicting_tasks(&"1").eq(&["2"]));
+
+    // Agent a cannot hold both tasks 1 and 2, so agent b takes task 2
+    let result = solve(&spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&Assignment::from_assigned(
+        [("a", vec!["1", "3"]), ("b", vec!["2"])],
+        &spec
+    )));
+
+    let mut assignment = Assignment::from_spec(&spec);
+    assert_eq!(assignment.assign(&"a", &"2"), Ok(()));
+    assert_eq!(
+        assignment.assign(&"a", &"1"),
+        Err(GapError::ConflictingTasks {
+            agent: "a",
+            task: "1",
+            conflict: "2"
+        })
+    );
+
+    spec.set_required([("b", "1"), ("b", "2")]);
+    assert_eq!(
+        spec.validate(),
+        Err(GapError::InvalidSpec(vec![GapError::ConflictingTasks {
+            agent: "b",
+            task: "2",
+            conflict: "1"
+        }]))
+    );
+}
+
+#[test]
+fn incompatible_agents() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b", "c"], ["1", "2"]);
+    spec.set_task_budgets([("1", 2), ("2", 2)]);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 1),
+        (("b", "1"), 3),
+        (("b", "2"), 1),
+        (("c", "1"), 1),
+        (("c", "2"), 2),
+    ]);
+    spec.set_incompatible([("a", "b")]);
+    assert!(spec.incompatible_agents(&"b").eq(&["a"]));
+
+    // Agents a and b cannot share task 1, so one of them takes task 2
+    let truth = HashSet::from([
+        Assignment::from_assigned(
+            [("a", vec!["1"]), ("b", vec!["2"]), ("c", vec!["2"])],
+            &spec,
+        ),
+        Assignment::from_assigned(
+            [("a", vec!["2"]), ("b", vec!["1"]), ("c", vec!["2"])],
+            &spec,
+        ),
+    ]);
+    assert_eq!(solve(&spec), truth);
+
+    let mut assignment = Assignment::from_spec(&spec);
+    assert_eq!(assignment.assign(&"a", &"1"), Ok(()));
+    assert_eq!(
+        assignment.assign(&"b", &"1"),
+        Err(GapError::IncompatibleAgents {
+            agent: "b",
+            task: "1",
+            other: "a"
+        })
+    );
+
+    spec.set_required([("a", "1"), ("b", "1")]);
+    assert_eq!(
+        spec.validate(),
+        Err(GapError::InvalidSpec(vec![GapError::IncompatibleAgents {
+            agent: "b",
+            task: "1",
+            other: "a"
+        }]))
+    );
+}
+
+#[test]
+fn paired_agents() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b", "c"], ["1", "2"]);
+    spec.set_task_budgets([("1", 2), ("2", 1)]);
+    spec.set_profits([
+        (("a", "1"), 1),
+        (("a", "2"), 3),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+        (("c", "1"), 2),
+        (("c", "2"), 1),
+    ]);
+    spec.set_paired([("a", "b")]);
+    assert!(spec.paired_agents(&"a").eq(&["b"]));
+
+    // Agents a and b only fit together on task 1
+    let result = solve(&spec);
+    assert_eq!(result.len(), 1);
+    let assignment = result.iter().next().unwrap();
+    assert_eq!(
+        assignment,
+        &Assignment::from_assigned(
+            [("a", vec!["1"]), ("b", vec!["1"]), ("c", vec!["2"])],
+            &spec
+        )
+    );
+    assert!(assignment.meets_pairings());
+
+    // Without room for both on task 1, no finished assignment keeps them together
+    spec.set_task_budgets([("1", 1), ("2", 1)]);
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+    assert_eq!(solution.status(), SolveStatus::Infeasible);
+    assert!(
+        !Assignment::from_assigned([("a", vec!["2"]), ("b", vec!["1"])], &spec).meets_pairings()
+    );
+}
+
+#[test]
+fn resource_budgets() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2", "3"]);
+    spec.set_agent_budgets([("a", 3), ("b", 3)]);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 3),
+        (("a", "3"), 3),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+        (("b", "3"), 5),
+    ]);
+    spec.set_agent_resources([("a", vec![2]), ("b", vec![1])]);
+    spec.set_agent_resource_costs(
+        spec.agents()
+            .iter()
+            .flat_map(|a| ["1", "2", "3"].map(|t| ((*a, t), vec![1])))
+            .collect::<Vec<_>>(),
+    );
+    spec.set_task_resources([("1", vec![1]), ("2", vec![1]), ("3", vec![1])]);
+    spec.set_task_resource_costs([(("b", "3"), vec![2])]);
+
+    // Agent a can only do two tasks, and agent b cannot do task 3
+    let truth = HashSet::from([
+        Assignment::from_assigned([("a", vec!["1", "3"]), ("b", vec!["2"])], &spec),
+        Assignment::from_assigned([("a", vec!["2", "3"]), ("b", vec!["1"])], &spec),
+    ]);
+    assert_eq!(solve(&spec), truth);
+
+    let mut assignment = Assignment::from_spec(&spec);
+    assert_eq!(assignment.assign(&"a", &"1"), Ok(()));
+    assert_eq!(assignment.assign(&"a", &"2"), Ok(()));
+    assert_eq!(assignment.agent_resources(&"a"), &[0]);
+    assert_eq!(
+        assignment.assign(&"a", &"3"),
+        Err(GapError::AgentResourceExceeded {
+            agent: "a",
+            resource: 0,
+            needed: 1,
+            remaining: 0
+        })
+    );
+    assert_eq!(
+        assignment.assign(&"b", &"3"),
+        Err(GapError::TaskResourceExceeded {
+            task: "3",
+            resource: 0,
+            needed: 2,
+            remaining: 1
+        })
+    );
+}
+
+#[test]
+fn validate_resources() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a", "b"], ["1", "2"]);
+    spec.set_agent_resources([("a", vec![1])]);
+    assert_eq!(
+        spec.validate(),
+        Err(GapError::InvalidSpec(vec![GapError::InconsistentResources]))
+    );
+
+    spec.set_agent_budgets([("a", 2), ("b", 1)]);
+    spec.set_agent_resources([("a", vec![1]), ("b", vec![1]), ("c", vec![1])]);
+    spec.set_agent_resource_costs([(("a", "1"), vec![1]), (("a", "2"), vec![1])]);
+    spec.set_assigned([("a", vec!["1", "2"])]);
+    let errors = vec![
+        GapError::UnknownAgent("c"),
+        GapError::AgentResourceExceeded {
+            agent: "a",
+            resource: 0,
+            needed: 2,
+            remaining: 1,
+        },
+    ];
+    assert_eq!(gap_solver::solve(&spec), Err(GapError::InvalidSpec(errors)));
+}
+
+#[test]
+fn options_without_limits() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    assert_eq!(solution.status(), SolveStatus::Optimal);
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments(), &solve(&spec));
+}
+
+#[test]
+fn node_limit() {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2", "3"];
+    let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+
+    let mut options = SolveOptions::new();
+    options.set_node_limit(5);
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::NodeLimit);
+    assert!(!solution.is_optimal());
+    assert_eq!(solution.nodes_expanded(), 5);
+}
+
+#[test]
+fn open_set_limit() {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2", "3"];
+    let spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+
+    let mut options = SolveOptions::new();
+    options.set_open_set_limit(1);
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::OpenSetLimit);
+    // The enumeration stops once it holds more maximum assignments than the limit
+    assert_eq!(solution.assignments().len(), 2);
+
+    // Unequal costs call for a search, which stops once its open set is too large
+    let mut spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 1), ("c", 1)]);
+    let agent_cost = agents.iter().flat_map(|a| {
+        tasks
+            .iter()
+            .map(move |t| ((*a, *t), if (*a, *t) == ("a", "1") { 2 } else { 1 }))
+    });
+    spec.set_agent_cost(agent_cost.collect());
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::OpenSetLimit);
+    assert_eq!(solution.nodes_expanded(), 1);
+}
+
+#[test]
+fn time_limit_on_fast_path() {
+    // Every one of the 10! matchings is a maximum assignment
+    let agents: Vec<u32> = (0..10).collect();
+    let tasks: Vec<u32> = (0..10).collect();
+    let spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+
+    let mut options = SolveOptions::new();
+    options.set_time_limit(Duration::from_millis(50));
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::TimeLimit);
+    assert!(!solution.assignments().is_empty());
+    assert!(solution.assignments().iter().all(|a| a.profit() == 10));
+    assert_eq!(solution.gap(), Some(0.0));
+
+    // Larger budgets make this a flow problem with far more maximum assignments
+    let agents: Vec<u32> = (0..7).collect();
+    let tasks: Vec<u32> = (0..7).collect();
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), tasks.clone());
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 2)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::TimeLimit);
+    assert!(!solution.assignments().is_empty());
+    assert!(solution.assignments().iter().all(|a| a.profit() == 14));
+
+    let mut options = SolveOptions::new();
+    options.set_node_limit(10);
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::NodeLimit);
+    assert_eq!(solution.nodes_expanded(), 10);
+}
+
+#[test]
+fn bound_prunes_search() {
+    let agents = [0, 1, 2, 3, 4, 5];
+    let tasks = [0, 1, 2, 3, 4, 5];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+
+    let profits: Vec<((u32, u32), u32)> = agents
+        .iter()
+        .flat_map(|a| {
+            tasks
+                .iter()
+                .map(move |t| ((*a, *t), if a == t { 10 } else { 1 }))
+        })
+        .collect();
+    spec.set_profits(profits);
+
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let assigned: Vec<(u32, Vec<u32>)> = agents.iter().map(|a| (*a, vec![*a])).collect();
+    let truth = Assignment::from_assigned(assigned, &spec);
+
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+    // Exhaustive search would visit every partial assignment
+    assert!(solution.nodes_expanded() < 100);
+}
+
+#[test]
+fn gap_when_cut_off() {
+    let agents = [0, 1, 2, 3, 4, 5];
+    let tasks = [0, 1, 2, 3, 4, 5, 6, 7];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 4)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let mut agent_cost = HashMap::new();
+    let mut profits = Vec::new();
+    for a in agents {
+        for t in tasks {
+            agent_cost.insert((a, t), (a * 5 + t * 2) % 3 + 1);
+            profits.push(((a, t), (a * 7 + t * 3) % 11 + 1));
+        }
+    }
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits(profits);
+
+    // Cut off the search
+    let mut options = SolveOptions::new();
+    options.set_node_limit(5);
+    let solution = solve_with_options(&spec, &options);
+
+    let profit = solution.assignments().iter().next().unwrap().profit();
+    assert_eq!(solution.status(), SolveStatus::NodeLimit);
+    assert!(solution.upper_bound() >= profit as f64);
+    assert!(solution.gap().unwrap() > 0.0);
+
+    // Search until finished
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let profit = solution.assignments().iter().next().unwrap().profit();
+    assert_eq!(solution.status(), SolveStatus::Optimal);
+    assert_eq!(solution.upper_bound(), profit as f64);
+    assert_eq!(solution.gap(), Some(0.0));
+}
+
+#[test]
+fn gap_of_zero_profit() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, i32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 1)]);
+    spec.set_agent_cost(
+        agents
+            .iter()
+            .flat_map(|a| {
+                tasks
+                    .iter()
+                    .map(move |t| ((*a, *t), if *a == "a" { 2 } else { 1 }))
+            })
+            .collect(),
+    );
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 2),
+        (("b", "1"), 2),
+        (("b", "2"), -3),
+    ]);
+
+    // The greedy assignment of a to 1 and b to 2 has no profit
+    let solution = solve_with_callback(&spec, &SolveOptions::new(), |_, _, _| Control::Stop);
+
+    let profit = solution.assignments().iter().next().unwrap().profit();
+    assert_eq!(profit, 0);
+    assert_eq!(solution.status(), SolveStatus::Stopped);
+    // The gap of an assignment without profit is the absolute difference
+    assert!(solution.upper_bound() >= 4.0);
+    assert_eq!(solution.gap(), Some(solution.upper_bound()));
+}
+
+#[test]
+fn lagrangian_small_problem_spec() {
+    // Setup
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2"];
+    let mut spec = GapSpec::new(agents, tasks);
+
+    let agent_budgets = [("a", 1), ("b", 2), ("c", 1)];
+    spec.set_agent_budgets(agent_budgets);
+
+    let task_budgets = [("1", 2), ("2", 2)];
+    spec.set_task_budgets(task_budgets);
+
+    let profits = [
+        (("a", "1"), 3.0),
+        (("a", "2"), 1.0),
+        (("b", "1"), 1.0),
+        (("b", "2"), 3.0),
+        (("c", "1"), 2.0),
+        (("c", "2"), 2.0),
+    ];
+    spec.set_profits(profits);
+
+    let assigned = [("a", vec!["1"])];
+    spec.set_assigned(assigned);
+
+    // Run
+    let result = solve_lagrangian(&spec, 100).unwrap();
+
+    // Assert
+    let assigned = [("a", vec!["1"]), ("b", vec!["1", "2"]), ("c", vec!["2"])];
+    let truth = Assignment::from_assigned(assigned, &spec);
+
+    assert_eq!(result.assignment(), &truth);
+    assert!(result.upper_bound() >= 9.0);
+    assert_eq!(result.gap(), 0.0);
+}
+
+#[test]
+fn lagrangian_bounds_maximum() {
+    let agents = [0, 1, 2, 3, 4, 5];
+    let tasks = [0, 1, 2, 3, 4, 5, 6, 7];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 4)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let mut agent_cost = HashMap::new();
+    let mut profits = Vec::new();
+    for a in agents {
+        for t in tasks {
+            agent_cost.insert((a, t), (a * 5 + t * 2) % 3 + 1);
+            profits.push(((a, t), (a * 7 + t * 3) % 11 + 1));
+        }
+    }
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits(profits);
+
+    let result = solve_lagrangian(&spec, 100).unwrap();
+    let maximum = solve(&spec).iter().next().unwrap().profit();
+
+    assert!(result.assignment().profit() <= maximum);
+    assert!(result.upper_bound() >= maximum as f64);
+    assert!(result.gap() < 0.1);
+}
+
+#[test]
+fn lagrangian_fractional_costs() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, f64, f64> = GapSpec::new(agents, tasks);
+
+    spec.set_agent_budgets([("a", 1.5), ("b", 1.0)]);
+
+    assert_eq!(
+        solve_lagrangian(&spec, 100).unwrap_err(),
+        GapError::NonIntegerBudget("a")
+    );
+}
+
+#[test]
+fn lagrangian_without_iterations() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+
+    // The bound is computed at least once
+    let result = solve_lagrangian(&spec, 0).unwrap();
+    assert_eq!(result.iterations(), 1);
+    assert_eq!(result.assignment().profit(), 2);
+    assert_eq!(result.upper_bound(), 2.0);
+    assert_eq!(result.gap(), 0.0);
+}
+
+#[test]
+fn lagrangian_large_budget() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 1), ("b", 1 << 22)]);
+
+    assert_eq!(
+        solve_lagrangian(&spec, 100).unwrap_err(),
+        GapError::KnapsackTooLarge("b")
+    );
+}
+
+#[test]
+fn linear_assignment_problem() {
+    let agents: Vec<u32> = (0..10).collect();
+    let tasks: Vec<u32> = (0..10).collect();
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), tasks.clone());
+
+    // Pairing agents and tasks in the same order maximizes the sum of products
+    let profits: Vec<((u32, u32), u32)> = agents
+        .iter()
+        .flat_map(|a| tasks.iter().map(move |t| ((*a, *t), (a + 1) * (t + 1))))
+        .collect();
+    spec.set_profits(profits);
+
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let assigned: Vec<(u32, Vec<u32>)> = agents.iter().map(|a| (*a, vec![*a])).collect();
+    let truth = Assignment::from_assigned(assigned, &spec);
+
+    assert!(solution.is_optimal());
+    // No tree is searched, but every step of enumerating the maximum matchings
+    // counts as a node, so that node limits also cut the enumeration short
+    assert_eq!(solution.nodes_expanded(), 56);
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+}
+
+#[test]
+fn linear_assignment_problem_ties() {
+    let agents = ["a", "b", "c", "d", "e"];
+    let tasks = ["1", "2", "3", "4", "5", "6"];
+    let mut spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+
+    let assigned = [("a", vec!["6"])];
+    spec.set_assigned(assigned);
+
+    let result = solve(&spec);
+
+    // The remaining four agents can do the remaining five tasks in 5 * 4 * 3 * 2 ways
+    assert_eq!(result.len(), 120);
+    assert!(result.iter().all(|a| a.profit() == 5.0));
+}
+
+#[test]
+fn min_cost_flow_problem() {
+    let agents = [0, 1, 2, 3];
+    let tasks = [0, 1, 2, 3];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 2)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let profits: Vec<((u32, u32), u32)> = agents
+        .iter()
+        .flat_map(|a| {
+            tasks
+                .iter()
+                .map(move |t| ((*a, *t), if a == t { 2 } else { 1 }))
+        })
+        .collect();
+    spec.set_profits(profits);
+
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    assert!(solution.is_optimal());
+    // Every step of enumerating the maximum flows counts as a node
+    assert_eq!(solution.nodes_expanded(), 17);
+    // Every agent does its own task, and the other tasks are handed out as a derangement
+    assert_eq!(solution.assignments().len(), 9);
+    assert!(solution.assignments().iter().all(|a| a.profit() == 12));
+}
+
+#[test]
+fn min_cost_flow_problem_ties() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3", "4"];
+    let mut spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2)]);
+
+    let result = solve(&spec);
+
+    // Agent a picks two of the four tasks, and agent b takes the others
+    assert_eq!(result.len(), 6);
+    assert!(result.iter().all(|a| a.profit() == 4.0));
+}
+
+#[test]
+fn knapsack_single_agent() {
+    let agents = ["a"];
+    let tasks = ["1", "2", "3", "4"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 5)]);
+
+    let agent_cost = HashMap::from([
+        (("a", "1"), 2),
+        (("a", "2"), 3),
+        (("a", "3"), 4),
+        (("a", "4"), 1),
+    ]);
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 4),
+        (("a", "3"), 6),
+        (("a", "4"), 1),
+    ]);
+
+    let result = solve_knapsack(&spec).unwrap();
+
+    // Tasks 1 and 2 are worth as much as tasks 3 and 4
+    let truth1 = Assignment::from_assigned([("a", vec!["1", "2"])], &spec);
+    let truth2 = Assignment::from_assigned([("a", vec!["3", "4"])], &spec);
+
+    assert_eq!(result.len(), 2);
+    assert!(result.contains(&truth1));
+    assert!(result.contains(&truth2));
+
+    // Every step of enumerating the maximum packings counts as a node
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+    assert_eq!(solution.nodes_expanded(), 9);
+    assert_eq!(solution.assignments(), &result);
+}
+
+#[test]
+fn knapsack_binding_task_budgets() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+
+    // Both agents compete for both tasks
+    assert_eq!(
+        solve_knapsack(&spec).unwrap_err(),
+        GapError::SharedTaskBudget("1")
+    );
+}
+
+#[test]
+fn knapsack_large_budget() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a"], ["1", "2"]);
+    spec.set_agent_budgets([("a", 1 << 22)]);
+
+    assert_eq!(
+        solve_knapsack(&spec).unwrap_err(),
+        GapError::KnapsackTooLarge("a")
+    );
+
+    // The main solver does not use dynamic programming for it
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().iter().next().unwrap().profit(), 2);
+}
+
+#[test]
+fn callback_reports_improvements() {
+    let agents = [0, 1, 2, 3];
+    let tasks = [0, 1, 2, 3, 4];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 3)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let mut agent_cost = HashMap::new();
+    let mut profits = Vec::new();
+    for a in agents {
+        for t in tasks {
+            agent_cost.insert((a, t), (a + t) % 2 + 1);
+            profits.push(((a, t), (a * 7 + t * 3) % 11 + 1));
+        }
+    }
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits(profits);
+
+    let mut reported = Vec::new();
+    let solution = solve_with_callback(&spec, &SolveOptions::new(), |assignment, _, _| {
+        reported.push(assignment.profit());
+        Control::Continue
+    });
+
+    let profit = solution.assignments().iter().next().unwrap().profit();
+    assert!(solution.is_optimal());
+    assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(reported.last(), Some(&profit));
+    assert!(reported.len() >= solution.assignments().len());
+}
+
+#[test]
+fn callback_stops_enumeration() {
+    // Every one of the 10! matchings is a maximum assignment
+    let agents: Vec<u32> = (0..10).collect();
+    let spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), agents);
+
+    // Maximum assignments are reported while they are enumerated, so stopping ends it
+    let mut calls = 0;
+    let solution = solve_with_callback(&spec, &SolveOptions::new(), |assignment, _, nodes| {
+        calls += 1;
+        assert_eq!(assignment.profit(), 10);
+        assert!(nodes > 0);
+        Control::Stop
+    });
+
+    assert_eq!(calls, 1);
+    assert_eq!(solution.status(), SolveStatus::Stopped);
+    assert_eq!(solution.assignments().len(), 1);
+    assert_eq!(solution.gap(), Some(0.0));
+}
+
+#[test]
+fn callback_stops_search() {
+    let agents = [0, 1, 2, 3];
+    let tasks = [0, 1, 2, 3, 4];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 3)));
+
+    let agent_cost = agents
+        .iter()
+        .flat_map(|a| tasks.iter().map(move |t| ((*a, *t), (a + t) % 2 + 1)))
+        .collect();
+    spec.set_agent_cost(agent_cost);
+
+    let mut calls = 0;
+    let solution = solve_with_callback(&spec, &SolveOptions::new(), |_, _, nodes| {
+        calls += 1;
+        assert_eq!(nodes, 0);
+        Control::Stop
+    });
+
+    assert_eq!(calls, 1);
+    assert_eq!(solution.status(), SolveStatus::Stopped);
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(
+        solution.upper_bound() >= solution.assignments().iter().next().unwrap().profit() as f64
+    );
+}
+
+#[test]
+fn cancellation_token() {
+    let agents = [0, 1, 2, 3];
+    let tasks = [0, 1, 2, 3, 4];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 3)));
+
+    let agent_cost = agents
+        .iter()
+        .flat_map(|a| tasks.iter().map(move |t| ((*a, *t), (a + t) % 2 + 1)))
+        .collect();
+    spec.set_agent_cost(agent_cost);
+
+    let token = CancellationToken::new();
+    let mut options = SolveOptions::new();
+    options.set_cancellation_token(token.clone());
+    assert!(!token.is_cancelled());
+
+    // Cancel from another thread
+    let handle = token.clone();
+    std::thread::spawn(move || handle.cancel()).join().unwrap();
+    assert!(token.is_cancelled());
+
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::Cancelled);
+    assert_eq!(solution.nodes_expanded(), 0);
+    // The greedy assignment is still returned
+    assert_eq!(solution.assignments().len(), 1);
+
+    // Problems solved without searching are cancelled as well
+    let spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, agents);
+    let solution = solve_with_options(&spec, &options);
+
+    assert_eq!(solution.status(), SolveStatus::Cancelled);
+    assert_eq!(solution.nodes_expanded(), 0);
+
+    // Cancelling from the callback stops the report of the maximum assignments
+    let token = CancellationToken::new();
+    options.set_cancellation_token(token.clone());
+    let mut reported = 0;
+    let solution = solve_with_callback(&spec, &options, |_, _, _| {
+        reported += 1;
+        token.cancel();
+        Control::Continue
+    });
+
+    assert_eq!(solution.status(), SolveStatus::Cancelled);
+    assert_eq!(reported, 1);
+}
+
+#[test]
+fn parallel_search() {
+    let agents = [0, 1, 2, 3];
+    let tasks = [0, 1, 2, 3, 4];
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets(agents.iter().map(|a| (*a, 3)));
+    spec.set_task_budgets(tasks.iter().map(|t| (*t, 2)));
+
+    let mut agent_cost = HashMap::new();
+    let mut profits = Vec::new();
+    for a in agents {
+        for t in tasks {
+            agent_cost.insert((a, t), (a + t) % 2 + 1);
+            profits.push(((a, t), (a * 7 + t * 3) % 5 + 1));
+        }
+    }
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits(profits);
+
+    let solution = solve_parallel(&spec, &SolveOptions::new(), 4);
+
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments(), &solve(&spec));
+
+    // Limits are respected as well
+    let mut options = SolveOptions::new();
+    options.set_node_limit(5);
+    let solution = solve_parallel(&spec, &options, 4);
+
+    assert_eq!(solution.status(), SolveStatus::NodeLimit);
+    assert!(solution.nodes_expanded() >= 5);
+    assert!(solution.gap().is_some());
+}
+
+#[test]
+fn minimize_medium_problem_spec() {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2", "3", "4"];
+    let mut spec = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2), ("c", 1)]);
+    spec.set_task_budgets([("1", 1), ("2", 1), ("3", 1), ("4", 2)]);
+
+    let agent_cost = HashMap::from([
+        (("a", "1"), 1),
+        (("a", "2"), 2),
+        (("a", "3"), 1),
+        (("a", "4"), 1),
+        (("b", "1"), 2),
+        (("b", "2"), 1),
+        (("b", "3"), 1),
+        (("b", "4"), 1),
+        (("c", "1"), 1),
+        (("c", "2"), 1),
+        (("c", "3"), 1),
+        (("c", "4"), 1),
+    ]);
+    spec.set_agent_cost(agent_cost);
+
+    // Profits are costs here, and should be as low as possible
+    let profits = [
+        (("a", "1"), 3u32),
+        (("a", "2"), 9),
+        (("a", "3"), 5),
+        (("a", "4"), 2),
+        (("b", "1"), 8),
+        (("b", "2"), 4),
+        (("b", "3"), 1),
+        (("b", "4"), 6),
+        (("c", "1"), 2),
+        (("c", "2"), 7),
+        (("c", "3"), 6),
+        (("c", "4"), 3),
+    ];
+    spec.set_profits(profits);
+    spec.set_sense(Sense::Minimize);
+
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    // Agent a is left with budget, but every task it could still do is taken
+    let assigned = [("a", vec!["4"]), ("b", vec!["2", "3"]), ("c", vec!["1"])];
+    let truth = Assignment::from_assigned(assigned, &spec);
+
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+    assert_eq!(solution.upper_bound(), 9.0);
+}
+
+#[test]
+fn minimize_linear_assignment_problem() {
+    let agents: Vec<u32> = (0..6).collect();
+    let tasks: Vec<u32> = (0..6).collect();
+    let mut spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), tasks.clone());
+
+    // Pairing agents and tasks in opposite order minimizes the sum of products
+    let profits: Vec<((u32, u32), u32)> = agents
+        .iter()
+        .flat_map(|a| tasks.iter().map(move |t| ((*a, *t), (a + 1) * (t + 1))))
+        .collect();
+    spec.set_profits(profits);
+    spec.set_sense(Sense::Minimize);
+
+    let result = solve(&spec);
+
+    let assigned: Vec<(u32, Vec<u32>)> = agents.iter().map(|a| (*a, vec![5 - a])).collect();
+    let truth = Assignment::from_assigned(assigned, &spec);
+
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+}
+
+#[test]
+fn complete_assignments() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 3), ("b", 2)]);
+    spec.set_task_budgets([("1", 2), ("2", 1), ("3", 1)]);
+
+    let agent_cost = HashMap::from([
+        (("a", "1"), 2),
+        (("a", "2"), 1),
+        (("a", "3"), 2),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+        (("b", "3"), 2),
+    ]);
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits([
+        (("a", "1"), 1),
+        (("a", "2"), 1),
+        (("a", "3"), 8),
+        (("b", "1"), 1),
+        (("b", "2"), 5),
+        (("b", "3"), 1),
+    ]);
+
+    // Without the constraint, agent a does task 3 and keeps one unit of budget
+    let result = solve(&spec);
+    let truth = Assignment::from_assigned([("a", vec!["3"]), ("b", vec!["1", "2"])], &spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+
+    spec.set_complete(true);
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let truth = Assignment::from_assigned([("a", vec!["1", "2"]), ("b", vec!["3"])], &spec);
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+
+    // A tolerance allows agent a to keep its unit of budget again
+    spec.set_agent_tolerances([("a", 1)]);
+    let result = solve(&spec);
+    let truth = Assignment::from_assigned([("a", vec!["3"]), ("b", vec!["1", "2"])], &spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+}
+
+#[test]
+fn complete_infeasible() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, f64> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 1)]);
+    spec.set_complete(true);
+
+    // Agent a cannot spend its budget, as agent b needs a task too
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    assert_eq!(solution.status(), SolveStatus::Infeasible);
+    assert!(solution.assignments().is_empty());
+    assert_eq!(solution.gap(), None);
+    assert_eq!(gap_solver::solve(&spec), Err(GapError::Infeasible));
+}
+
+#[test]
+fn agent_minimums() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2)]);
+    spec.set_profits([
+        (("a", "1"), 5),
+        (("a", "2"), 5),
+        (("a", "3"), 1),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+        (("b", "3"), 1),
+    ]);
+
+    // Agent b must do two tasks, leaving a single task for agent a
+    spec.set_agent_minimums([("b", 2)]);
+    let result = solve(&spec);
+    let truth = [
+        Assignment::from_assigned([("a", vec!["1"]), ("b", vec!["2", "3"])], &spec),
+        Assignment::from_assigned([("a", vec!["2"]), ("b", vec!["1", "3"])], &spec),
+    ];
+    assert_eq!(result.len(), truth.len());
+    for assignment in &truth {
+        assert!(result.contains(assignment));
+        assert!(assignment.meets_minimums());
+    }
+}
+
+#[test]
+fn task_minimums() {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_task_budgets([("1", 2), ("2", 2)]);
+    spec.set_profits([
+        (("a", "1"), 3),
+        (("a", "2"), 1),
+        (("b", "1"), 3),
+        (("b", "2"), 1),
+        (("c", "1"), 1),
+        (("c", "2"), 1),
+    ]);
+
+    // Task 2 must be covered by two agents
+    spec.set_task_minimums([("2", 2)]);
+    let result = solve(&spec);
+    let truth = [
+        Assignment::from_assigned(
+            [("a", vec!["1"]), ("b", vec!["2"]), ("c", vec!["2"])],
+            &spec,
+        ),
+        Assignment::from_assigned(
+            [("a", vec!["2"]), ("b", vec!["1"]), ("c", vec!["2"])],
+            &spec,
+        ),
+    ];
+    assert_eq!(result.len(), truth.len());
+    for assignment in &truth {
+        assert!(result.contains(assignment));
+    }
+
+    // Task 2 cannot be covered by three agents within its budget
+    spec.set_task_minimums([("2", 3)]);
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+    assert_eq!(solution.status(), SolveStatus::Infeasible);
+}
+
+#[test]
+fn fairness_tie_break() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2)]);
+
+    // Every maximal assignment has the same profit
+    let result = solve(&spec);
+    assert_eq!(result.len(), 4);
+
+    spec.set_fairness(Fairness::TieBreak);
+    let result = solve(&spec);
+
+    let truth = [
+        Assignment::from_assigned([("a", vec!["1"]), ("b", vec!["2"])], &spec),
+        Assignment::from_assigned([("a", vec!["2"]), ("b", vec!["1"])], &spec),
+    ];
+    assert_eq!(result.len(), truth.len());
+    for assignment in &truth {
+        assert!(result.contains(assignment));
+        assert_eq!(assignment.agent_profit(&"a"), 1);
+        assert_eq!(assignment.agent_profit(&"b"), 1);
+    }
+}
+
+#[test]
+fn fairness_primary() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_profits([
+        (("a", "1"), 10),
+        (("a", "2"), 3),
+        (("b", "1"), 4),
+        (("b", "2"), 1),
+    ]);
+
+    // Ties are broken by fairness only if the profit is the same
+    spec.set_fairness(Fairness::TieBreak);
+    let result = solve(&spec);
+    let truth = Assignment::from_assigned([("a", vec!["1"]), ("b", vec!["2"])], &spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+
+    spec.set_fairness(Fairness::Primary);
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let truth = Assignment::from_assigned([("a", vec!["2"]), ("b", vec!["1"])], &spec);
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+    assert_eq!(solution.upper_bound(), 3.0);
+}
+
+#[test]
+fn fairness_primary_ties() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2)]);
+    spec.set_profits([
+        (("a", "1"), 5),
+        (("a", "2"), 1),
+        (("a", "3"), 1),
+        (("b", "1"), 1),
+        (("b", "2"), 5),
+        (("b", "3"), 0),
+    ]);
+    spec.set_fairness(Fairness::Primary);
+
+    // Two assignments leave the worst-off agent a profit of 5; the most profitable one wins
+    let result = solve(&spec);
+
+    let truth = Assignment::from_assigned([("a", vec!["1", "3"]), ("b", vec!["2"])], &spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+}
+
+#[test]
+fn lexicographic_objectives() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 3), ("b", 2)]);
+    spec.set_task_budgets([("1", 1), ("2", 1), ("3", 1)]);
+    let agent_cost = HashMap::from([
+        (("a", "1"), 1),
+        (("a", "2"), 1),
+        (("a", "3"), 3),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+        (("b", "3"), 2),
+    ]);
+    spec.set_agent_cost(agent_cost);
+
+    // Two assignments do all three tasks
+    let result = solve(&spec);
+    assert_eq!(result.len(), 2);
+
+    // Only agent a doing task 3 leaves no budget unspent
+    spec.set_objectives([Objective::Profit, Objective::Leftover])
+        .unwrap();
+    let result = solve(&spec);
+    let truth = Assignment::from_assigned([("a", vec!["3"]), ("b", vec!["1", "2"])], &spec);
+    assert_eq!(result.len(), 1);
+    assert!(result.contains(&truth));
+
+    // A custom objective takes precedence over the leftover when listed first
+    spec.set_objectives([
+        Objective::Profit,
+        Objective::Custom(Box::new(|assignment| {
+            assignment.agent_tasks(&"a").map_or(0, |tasks| tasks.len()) as f64
+        })),
+        Objective::Leftover,
+    ])
+    .unwrap();
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+    let truth = Assignment::from_assigned([("a", vec!["1", "2"]), ("b", vec!["3"])], &spec);
+    assert!(solution.is_optimal());
+    assert_eq!(solution.assignments().len(), 1);
+    assert!(solution.assignments().contains(&truth));
+}
+
+#[test]
+fn leftover_primary_objective() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 2)]);
+    let agent_cost = HashMap::from([
+        (("a", "1"), 1),
+        (("a", "2"), 1),
+        (("b", "1"), 2),
+        (("b", "2"), 2),
+    ]);
+    spec.set_agent_cost(agent_cost);
+    spec.set_profits([
+        (("a", "1"), 5),
+        (("a", "2"), 5),
+        (("b", "1"), 1),
+        (("b", "2"), 1),
+    ]);
+
+    // Giving both tasks to agent a is most profitable, but leaves b idle
+    spec.set_objectives([Objective::Leftover, Objective::Profit])
+        .unwrap();
+    let solution = solve_with_options(&spec, &SolveOptions::new());
+
+    let truth = [
+        Assignment::from_assigned([("a", vec!["1"]), ("b", vec!["2"])], &spec),
+        Assignment::from_assigned([("a", vec!["2"]), ("b", vec!["1"])], &spec),
+    ];
+    assert!(solution.is_optimal());
+    assert_eq!(solution.upper_bound(), 1.0);
+    assert_eq!(solution.assignments().len(), truth.len());
+    for assignment in &truth {
+        assert!(solution.assignments().contains(assignment));
+    }
+
+    assert_eq!(spec.set_objectives([]), Err(GapError::NoObjectives));
+}
+
+#[test]
+fn pareto_front() {
+    let agents = ["a", "b"];
+    let tasks = ["1", "2", "3"];
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(agents, tasks);
+    spec.set_agent_budgets([("a", 2), ("b", 1)]);
+
+    // Profit against a second dimension, e.g. the preference of the agents
+    spec.set_profit_vectors([
+        (("a", "1"), vec![3, 0]),
+        (("a", "2"), vec![3, 0]),
+        (("a", "3"), vec![0, 4]),
+        (("b", "1"), vec![2, 1]),
+        (("b", "2"), vec![1, 1]),
+        (("b", "3"), vec![0, 3]),
+    ]);
+    let solution = solve_pareto(&spec, &SolveOptions::new()).unwrap();
+
+    // The vectors are (6, 3), (4, 5) and (5, 5); the second is dominated by the third
+    let truth = [
+        Assignment::from_assigned([("a", vec!["1", "2"]), ("b", vec!["3"])], &spec),
+        Assignment::from_assigned([("a", vec!["2", "3"]), ("b", vec!["1"])], &spec),
+    ];
+    assert!(solution.is_optimal());
+    assert_eq!(solution.upper_bound(), 6.0);
+    assert_eq!(solution.assignments().len(), truth.len());
+    for assignment in &truth {
+        assert!(solution.assignments().contains(assignment));
+    }
+}
+
+#[test]
+fn pareto_missing_vectors() {
+    let mut spec: GapSpec<&str, &str, u32, u32> = GapSpec::new(["a"], ["1", "2"]);
+    spec.set_profit_vectors([(("a", "1"), vec![1, 2])]);
+    assert_eq!(
+        solve_pareto(&spec, &SolveOptions::new()).unwrap_err(),
+        GapError::MissingProfitVector {
+            agent: "a",
+            task: "2"
+        }
+    );
+
+    spec.set_profit_vectors([(("a", "1"), vec![1, 2]), (("a", "2"), vec![1])]);
+    assert_eq!(
+        solve_pareto(&spec, &SolveOptions::new()).unwrap_err(),
+        GapError::InconsistentProfitVectors
+    );
+}
+
+fn pool_problem_spec() -> GapSpec<&'static str, &'static str, u32, u32> {
+    let agents = ["a", "b", "c"];
+    let tasks = ["1", "2", "3"];
+    let mut spec = GapSpec::new(agents, tasks);
+    spec.set_profits([
+        (("a", "1"), 6),
+        (("a", "2"), 2),
+        (("a", "3"), 1),
+        (("b", "1"), 2),
+        (("b", "2"), 5),
+        (("b", "3"), 1),
+        (("c", "1"), 1),
+        (("c", "2"), 1),
+        (("c", "3"), 4),
+    ]);
+    spec
+}
+
+#[test]
+fn pool_top_k() {
+    let spec = pool_problem_spec();
+    let mut pool_options = PoolOptions::new();
+    pool_options.set_size(3);
+
+    let pool = solve_pool(&spec, &SolveOptions::new(), &pool_options);
+
+    // The six permutations have profits 15, 8, 8, 7, 4 and 4
+    let profits: Vec<u32> = pool.assignments().iter().map(|a| a.profit()).collect();
+    let best = Assignment::from_assigned([("a", ["1"]), ("b", ["2"]), ("c", ["3"])], &spec);
+    assert!(pool.is_optimal());
+    assert_eq!(profits, vec![15, 8, 8]);
+    assert_eq!(pool.assignments()[0], best);
+}
+
+#[test]
+fn pool_gap() {
+    let spec = pool_problem_spec();
+    let mut pool_options = PoolOptions::new();
+    pool_options.set_gap(0.5);
+
+    let pool = solve_pool(&spec, &SolveOptions::new(), &pool_options);
+    let profits: Vec<u32> = pool.assignments().iter().map(|a| a.profit()).collect();
+    assert_eq!(profits, vec![15, 8, 8]);
+
+    pool_options.set_gap(0.55);
+    let pool = solve_pool(&spec, &SolveOptions::new(), &pool_options);
+    let profits: Vec<u32> = pool.assignments().iter().map(|a| a.profit()).collect();
+    assert_eq!(profits, vec![15, 8, 8, 7]);
+}
+
+#[test]
+fn pool_empty() {
+    let spec = pool_problem_spec();
+    let mut pool_options = PoolOptions::new();
+    pool_options.set_size(0);
+    pool_options.set_gap(0.5);
+
+    let result = gap_solver::solve_pool(&spec, &SolveOptions::new(), &pool_options);
+    assert_eq!(result.unwrap_err(), GapError::EmptyPool);
+}
+
+#[test]
+fn pool_min_difference() {
+    let spec = pool_problem_spec();
+    let mut pool_options = PoolOptions::new();
+    pool_options.set_size(3);
+    pool_options.set_min_difference(6);
+
+    // Only the permutations that move every agent differ from the best in six pairs
+    let pool = solve_pool(&spec, &SolveOptions::new(), &pool_options);
+
+    let profits: Vec<u32> = pool.assignments().iter().map(|a| a.profit()).collect();
+    let truth = [
+        Assignment::from_assigned([("a", ["2"]), ("b", ["3"]), ("c", ["1"])], &spec),
+        Assignment::from_assigned([("a", ["3"]), ("b", ["1"]), ("c", ["2"])], &spec),
+    ];
+    assert_eq!(profits, vec![15, 4, 4]);
+    for assignment in &truth {
+        assert!(pool.assignments().contains(assignment));
+    }
+}
+
+#[test]
+fn iterate_optimal_assignments() {
+    let agents: Vec<u32> = (0..10).collect();
+    let spec: GapSpec<u32, u32, u32, u32> = GapSpec::new(agents.clone(), agents);
+
+    // Taking a few of the 3628800 maximum assignments only searches a small part of the tree,
+    // even though the problem is a linear assignment problem
+    let mut iter = solve_iter(&spec, &SolveOptions::new());
+    let first: HashSet<Assignment<u32, u32, u32, u32>> = iter.by_ref().take(3).collect();
+    assert_eq!(first.len(), 3);
+    assert!(first.iter().all(|assignment| assignment.profit() == 10));
+    assert_eq!(iter.status(), None);
+    assert!(iter.nodes_expanded() < 1000);
+}
+
+#[test]
+fn iterate_all_optimal_assignments() {
+    let spec = pool_problem_spec();
+    let mut iter = solve_iter(&spec, &SolveOptions::new());
+    let result: Vec<Assignment<&str, &str, u32, u32>> = iter.by_ref().collect();
+
+    let truth = Assignment::from_assigned([("a", ["1"]), ("b", ["2"]), ("c", ["3"])], &spec);
+    assert_eq!(result, vec![truth]);
+    assert_eq!(iter.status(), Some(SolveStatus::Optimal));
+
+    // Nothing is proven optimal before the search is cut off
+    let mut options = SolveOptions::new();
+    options.set_node_limit(1);
+    let mut iter = solve_iter(&spec, &options);
+    assert!(iter.next().is_none());
+    assert_eq!(iter.status(), Some(SolveStatus::NodeLimit));
+}